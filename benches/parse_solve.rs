@@ -1,6 +1,6 @@
 use criterion::*;
 use rs_sudoku::examples::*;
-use rs_sudoku::{parse_sudoku, SolveReport, Solveable};
+use rs_sudoku::{parse_sudoku, DancingLinks, SolveReport, Solveable, Sudoku};
 
 fn parse_solve(p_str: &str) -> Option<SolveReport> {
     if let Ok(p) = parse_sudoku(p_str) {
@@ -12,6 +12,12 @@ fn parse_solve(p_str: &str) -> Option<SolveReport> {
     }
 }
 
+fn parse_dlx(p_str: &str) -> Option<Sudoku> {
+    let solution = parse_sudoku(p_str).ok()?.dancing_links();
+    assert!(solution.is_some());
+    solution
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("easy", |b| b.iter(|| parse_solve(ONE_LINE)));
 
@@ -21,6 +27,12 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| PUZZLES.lines().filter_map(parse_solve).count())
     });
     c.bench_function("hard", |b| b.iter(|| parse_solve(HARD)));
+
+    c.bench_function("easy_dlx", |b| b.iter(|| parse_dlx(ONE_LINE)));
+    c.bench_function("lots_dlx", |b| {
+        b.iter(|| PUZZLES.lines().filter_map(parse_dlx).count())
+    });
+    c.bench_function("hard_dlx", |b| b.iter(|| parse_dlx(HARD)));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,357 @@
+use crate::candidate_set::CandidateSetIterator;
+use crate::fast_index::group_comb_iter;
+use crate::step::{to_digit, Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+use std::collections::{HashSet, VecDeque};
+
+pub trait AlmostLockedSets {
+    fn find_als_xz(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_als_xy_wing(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_als_chain(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// An Almost Locked Set: N unsolved cells in one
+/// unit with N + 1 candidates between them.
+#[derive(Debug)]
+struct Als {
+    unit: Unit,
+    cells: Vec<usize>,
+    /// A bit mask of `cells`.
+    cell_mask: u128,
+    /// Every candidate in the set.
+    mask: usize,
+    /// For each digit the cells that see every cell
+    /// of the set with that digit.
+    seen: [u128; 9],
+}
+
+/// A bit mask of the cells in `cells`.
+fn cell_mask(cells: impl Iterator<Item = usize>) -> u128 {
+    cells.fold(0, |a, i| a | (1 << i))
+}
+
+fn cells_of(mask: u128) -> impl Iterator<Item = usize> {
+    (0..81).filter(move |i| mask & (1 << i) != 0)
+}
+
+/// Every almost locked set in every unit from one cell up to eight.
+/// A set that's in both a line and a box is only kept once.
+fn find_sets(sudoku: &Sudoku) -> Vec<Als> {
+    let peers: Vec<u128> = (0..81).map(|i| sudoku.peer_mask(i)).collect();
+    let mut found = HashSet::new();
+    let mut sets = Vec::new();
+    for unit in sudoku.houses() {
+        let house = sudoku.house(unit);
+        let open = house.iter().filter(|i| !sudoku[**i].is_solved()).count();
+        for size in 1..open.min(8) + 1 {
+            for cells in group_comb_iter(size) {
+                let cells: Vec<usize> = cells.into_iter().map(|n| house[n]).collect();
+                if cells.iter().any(|i| sudoku[*i].is_solved()) {
+                    continue;
+                }
+                let mask = cells.iter().fold(0, |a, i| a | sudoku[*i].get());
+                if mask.count_ones() as usize != size + 1 {
+                    continue;
+                }
+                let cm = cell_mask(cells.iter().cloned());
+                if !found.insert(cm) {
+                    continue;
+                }
+                let mut seen = [0; 9];
+                for (val_idx, s) in seen.iter_mut().enumerate() {
+                    *s = cells
+                        .iter()
+                        .filter(|i| sudoku[**i].get() & (1 << val_idx) != 0)
+                        .fold((1 << 81) - 1, |a, i| a & peers[*i]);
+                }
+                sets.push(Als {
+                    unit,
+                    cells,
+                    cell_mask: cm,
+                    mask,
+                    seen,
+                });
+            }
+        }
+    }
+    sets
+}
+
+/// All the sets along with the restricted common candidates
+/// between every pair of them.
+struct AlsGraph {
+    sets: Vec<Als>,
+    /// For each set the other sets it shares a restricted
+    /// common candidate with, and the mask of those candidates.
+    rcc: Vec<Vec<(usize, usize)>>,
+    /// For each digit the unsolved cells that have it.
+    digit_cells: [u128; 9],
+}
+
+impl AlsGraph {
+    fn new(sudoku: &Sudoku) -> Self {
+        let sets = find_sets(sudoku);
+        let mut digit_cells = [0; 9];
+        for (val_idx, d) in digit_cells.iter_mut().enumerate() {
+            *d = cell_mask(
+                (0..81)
+                    .filter(|i| !sudoku[*i].is_solved() && sudoku[*i].get() & (1 << val_idx) != 0),
+            );
+        }
+        let mut rcc = vec![Vec::new(); sets.len()];
+        for (a, set_a) in sets.iter().enumerate() {
+            for (b, set_b) in sets.iter().enumerate().skip(a + 1) {
+                if set_a.cell_mask & set_b.cell_mask != 0 {
+                    continue;
+                }
+                // A digit is restricted common if every cell with it in
+                // one set sees every cell with it in the other. Then it
+                // can only be in one of the two sets.
+                let common = CandidateSetIterator::new(set_a.mask & set_b.mask)
+                    .filter(|m| {
+                        let val_idx = m.trailing_zeros() as usize;
+                        digit_cells[val_idx] & set_b.cell_mask & !set_a.seen[val_idx] == 0
+                    })
+                    .fold(0, |acc, m| acc | m);
+                if common != 0 {
+                    rcc[a].push((b, common));
+                    rcc[b].push((a, common));
+                }
+            }
+        }
+        AlsGraph {
+            sets,
+            rcc,
+            digit_cells,
+        }
+    }
+
+    /// The unsolved cells with `m` that see every cell with it in all of `sets`.
+    fn seen_by_sets(&self, sets: &[usize], m: usize) -> u128 {
+        let val_idx = m.trailing_zeros() as usize;
+        sets.iter().fold(self.digit_cells[val_idx], |a, s| {
+            a & self.sets[*s].seen[val_idx]
+        })
+    }
+
+    fn step(
+        &self,
+        technique: Technique,
+        sets: &[usize],
+        rccs: &[usize],
+        eliminations: &[(u128, usize)],
+    ) -> Option<Step> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for (cells, m) in eliminations.iter() {
+            for i in cells_of(*cells) {
+                let c = Candidate::new(i, to_digit(*m));
+                if !candidates.contains(&c) {
+                    candidates.push(c);
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by_key(|c| (c.index, c.digit));
+        Some(Step {
+            technique,
+            units: sets.iter().map(|s| self.sets[*s].unit).collect(),
+            cells: sets
+                .iter()
+                .flat_map(|s| self.sets[*s].cells.iter().cloned())
+                .collect(),
+            digits: rccs.iter().map(|m| to_digit(*m)).collect(),
+            eliminations: candidates,
+            placements: vec![],
+            links: vec![],
+            sets: sets.iter().map(|s| self.sets[*s].cells.clone()).collect(),
+        })
+    }
+
+    /// Two sets joined by a restricted common candidate x. One of them
+    /// has to be locked, so any other common digit z has to be in one
+    /// of them.
+    ///
+    /// If they're joined by two then both sets are locked.
+    fn xz_steps(&self, a: usize) -> Vec<Step> {
+        let mut steps = Vec::new();
+        for (b, common) in self.rcc[a].iter().filter(|(b, _)| *b > a) {
+            let pair = [a, *b];
+            let both = self.sets[a].mask & self.sets[*b].mask;
+            let rccs: Vec<usize> = CandidateSetIterator::new(*common).collect();
+            let eliminations: Vec<(u128, usize)> = if rccs.len() == 1 {
+                CandidateSetIterator::new(both & !common)
+                    .map(|z| (self.seen_by_sets(&pair, z), z))
+                    .collect()
+            } else {
+                let mut e: Vec<(u128, usize)> = rccs
+                    .iter()
+                    .map(|x| (self.seen_by_sets(&pair, *x), *x))
+                    .collect();
+                for s in pair {
+                    e.extend(
+                        CandidateSetIterator::new(self.sets[s].mask & !common)
+                            .map(|d| (self.seen_by_sets(&[s], d), d)),
+                    );
+                }
+                e
+            };
+            steps.extend(self.step(Technique::AlsXZ, &pair, &rccs, &eliminations));
+        }
+        steps
+    }
+
+    /// Two sets joined to a pivot set through different restricted
+    /// common candidates x and y. A common digit z of the two
+    /// wings has to be in one of them.
+    fn xy_wing_steps(&self, pivot: usize) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let links = &self.rcc[pivot];
+        for (n, (a, x_mask)) in links.iter().enumerate() {
+            for (b, y_mask) in links[n + 1..].iter() {
+                if self.sets[*a].cell_mask & self.sets[*b].cell_mask != 0 {
+                    continue;
+                }
+                for x in CandidateSetIterator::new(*x_mask) {
+                    for y in CandidateSetIterator::new(*y_mask & !x) {
+                        let wings = [*a, *b];
+                        let both = self.sets[*a].mask & self.sets[*b].mask & !x & !y;
+                        let eliminations: Vec<(u128, usize)> = CandidateSetIterator::new(both)
+                            .map(|z| (self.seen_by_sets(&wings, z), z))
+                            .collect();
+                        steps.extend(self.step(
+                            Technique::AlsXYWing,
+                            &[*a, pivot, *b],
+                            &[x, y],
+                            &eliminations,
+                        ));
+                    }
+                }
+            }
+        }
+        steps
+    }
+
+    /// Sets joined one after the other by restricted common candidates,
+    /// never using the same one twice in a row. If z isn't in the first
+    /// set then each set after it is locked, so z is in the last.
+    ///
+    /// Searches out from `start` shortest first and keeps chains
+    /// of four or more sets; the shorter ones are ALS-XZ and ALS-XY-Wing.
+    fn chain_steps(&self, start: usize) -> Vec<Step> {
+        // States are (set, the candidate used to get there).
+        let state = |set: usize, m: usize| set * 9 + m.trailing_zeros() as usize;
+        let mut parent: Vec<Option<usize>> = vec![None; self.sets.len() * 9];
+        let mut seen = vec![false; self.sets.len() * 9];
+        let mut queue = VecDeque::new();
+        for (b, common) in self.rcc[start].iter() {
+            for x in CandidateSetIterator::new(*common) {
+                seen[state(*b, x)] = true;
+                queue.push_back(state(*b, x));
+            }
+        }
+        let mut steps = Vec::new();
+        while let Some(cur) = queue.pop_front() {
+            let (set, last) = (cur / 9, 1 << (cur % 9));
+            let mut path = vec![cur];
+            while let Some(p) = parent[*path.last().unwrap()] {
+                path.push(p);
+            }
+            path.reverse();
+            let first = 1 << (path[0] % 9);
+            if path.len() >= 3 {
+                let ends = [start, set];
+                let both = self.sets[start].mask & self.sets[set].mask & !first & !last;
+                let eliminations: Vec<(u128, usize)> = CandidateSetIterator::new(both)
+                    .map(|z| (self.seen_by_sets(&ends, z), z))
+                    .collect();
+                let sets: Vec<usize> = std::iter::once(start)
+                    .chain(path.iter().map(|s| s / 9))
+                    .collect();
+                let rccs: Vec<usize> = path.iter().map(|s| 1 << (s % 9)).collect();
+                steps.extend(self.step(Technique::AlsChain, &sets, &rccs, &eliminations));
+            }
+            for (next, common) in self.rcc[set].iter() {
+                if *next == start || path.iter().any(|s| s / 9 == *next) {
+                    continue;
+                }
+                for x in CandidateSetIterator::new(*common & !last) {
+                    let s = state(*next, x);
+                    if !seen[s] {
+                        seen[s] = true;
+                        parent[s] = Some(cur);
+                        queue.push_back(s);
+                    }
+                }
+            }
+        }
+        steps
+    }
+}
+
+impl AlmostLockedSets for Sudoku {
+    fn find_als_xz(&self) -> impl Iterator<Item = Step> + '_ {
+        let graph = AlsGraph::new(self);
+        (0..graph.sets.len()).flat_map(move |a| graph.xz_steps(a))
+    }
+
+    fn find_als_xy_wing(&self) -> impl Iterator<Item = Step> + '_ {
+        let graph = AlsGraph::new(self);
+        (0..graph.sets.len()).flat_map(move |pivot| graph.xy_wing_steps(pivot))
+    }
+
+    fn find_als_chain(&self) -> impl Iterator<Item = Step> + '_ {
+        let graph = AlsGraph::new(self);
+        (0..graph.sets.len()).flat_map(move |start| graph.chain_steps(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_helpers::to_index;
+    use crate::test_helpers::step_until;
+
+    /// Every set has one more candidate than it has cells.
+    fn assert_almost_locked(p: &Sudoku, step: &Step) {
+        for set in step.sets.iter() {
+            assert!(set.iter().all(|i| !p[*i].is_solved()));
+            let union = set.iter().fold(0, |a, i| a | p[*i].get_candidates());
+            assert_eq!(set.len() + 1, union.count_ones() as usize);
+        }
+        assert_eq!(step.units.len(), step.sets.len());
+    }
+
+    #[test]
+    fn test_als_xz() {
+        let s = "......2..5.....71....763....4.5..12...8........3.19..462.8...4...5.9......9....82";
+        let (p, step) = step_until(s, Technique::AlsXZ);
+        assert_almost_locked(&p, &step);
+        assert_eq!(2, step.sets.len());
+        assert_eq!(vec![Candidate::new(to_index(8, 3), 3)], step.eliminations);
+    }
+
+    #[test]
+    fn test_als_xy_wing() {
+        let s = "..1.7...4....2..9.43...6......867..13.6...5...7.3....26...4.8..7.36.9.2..4.......";
+        let (p, step) = step_until(s, Technique::AlsXYWing);
+        assert_almost_locked(&p, &step);
+        assert_eq!(3, step.sets.len());
+        // The pivot is joined to each wing by a different candidate.
+        assert_eq!(2, step.digits.len());
+        assert_ne!(step.digits[0], step.digits[1]);
+        assert_eq!(vec![Candidate::new(to_index(2, 7), 1)], step.eliminations);
+    }
+
+    #[test]
+    fn test_als_chain() {
+        let s = "2.....36..7...31..8..9....5..4.6..29.1.4.....6...3.......1...5.....94..6.6...8...";
+        let (p, step) = step_until(s, Technique::AlsChain);
+        assert_almost_locked(&p, &step);
+        assert!(step.sets.len() >= 4);
+        assert_eq!(step.sets.len() - 1, step.digits.len());
+        assert!(step.digits.windows(2).all(|w| w[0] != w[1]));
+        assert_eq!(vec![Candidate::new(to_index(5, 5), 2)], step.eliminations);
+    }
+}
@@ -1,6 +1,29 @@
 use rs_sudoku::slow_index::*;
+use rs_sudoku::BoxSize;
 
 type IterFn = dyn Fn(usize) -> RelatedIndexIterator;
+
+/// Print one table with the positions of every house in turn.
+fn print_table(name: &str, houses: Vec<Vec<usize>>) {
+    let len: usize = houses.iter().map(|h| h.len()).sum();
+    let d = houses
+        .iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|u| format!("{:3 }", u))
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+        .collect::<Vec<String>>();
+    println!(
+        "pub const {} : [u16; {}] = [\n        {}];",
+        name,
+        len,
+        d.join(",\n        ")
+    );
+}
+
 fn main() {
     let fields: [(&str, Box<IterFn>); 3] = [
         (
@@ -27,18 +50,23 @@ fn main() {
     ];
 
     for (name, g) in fields.iter() {
-        let d = (0..9)
-            .map(|idx| {
-                g(idx)
-                    .map(|u| format!("{:3 }", u))
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            })
-            .collect::<Vec<String>>();
-        println!(
-            "pub const {} : [u8; 81] = [\n        {}];",
-            name,
-            d.join(",\n        ")
-        );
+        print_table(name, (0..9).map(|idx| g(idx).collect()).collect());
+    }
+
+    // The other sizes are suffixed with how many digits they have.
+    for size in [
+        BoxSize::FOUR,
+        BoxSize::SIX,
+        BoxSize::SIXTEEN,
+        BoxSize::TWENTY_FIVE,
+    ] {
+        let n = size.digits();
+        let houses = size.houses();
+        for (name, group) in ["ROW_DATA", "COLUMN_DATA", "BOX_DATA"]
+            .iter()
+            .zip(houses.chunks(n))
+        {
+            print_table(&format!("{}_{}", name, n), group.to_vec());
+        }
     }
 }
@@ -1,49 +1,64 @@
 use crate::index::Grouping;
 use crate::index_helpers::*;
-use crate::remove_mask::*;
+use crate::step::{Candidate, Step, Technique, Unit};
 use crate::sudoku::Sudoku;
 
 pub trait LineBoxReduction {
-    fn box_line_reduce(&mut self) -> (usize, usize);
+    fn find_box_line(&self) -> impl Iterator<Item = Step> + '_;
 }
 
-impl LineBoxReduction for Sudoku {
-    fn box_line_reduce(&mut self) -> (usize, usize) {
-        [Grouping::Row, Grouping::Column]
-            .iter()
-            .find_map(|g| {
-                (0..9).find_map(|g_idx| {
-                    let mut val_pos: [usize; 9] = [0; 9];
-                    for idx in g.iter(g_idx) {
-                        let (_, _, box_i) = get_index_tuple(idx);
-                        for c in self[idx] {
-                            val_pos[c.trailing_zeros() as usize] |= 1 << box_i;
-                        }
-                    }
-                    let removed: usize = val_pos
-                        .iter()
-                        .enumerate()
-                        .filter(|(_val_idx, val_pos_mask)| val_pos_mask.count_ones() == 1)
-                        .map(|(val_idx, val_pos_mask)| {
-                            let box_i = val_pos_mask.trailing_zeros() as usize;
-                            let iter = Grouping::Box.iter(box_i).filter(|i| {
-                                let (r, c, _b) = get_index_tuple(*i);
-                                match g {
-                                    Grouping::Row => r != g_idx,
-                                    Grouping::Column => c != g_idx,
-                                    _ => true,
-                                }
-                            });
-                            self.remove_mask(1 << val_idx, iter)
-                        })
-                        .sum();
-                    if removed > 0 {
-                        Some((removed, 0))
-                    } else {
-                        None
+fn box_line_in_line(sudoku: &Sudoku, g: Grouping, g_idx: usize) -> Vec<Step> {
+    let mut val_pos: [usize; 9] = [0; 9];
+    for idx in g.iter(g_idx) {
+        let box_i = sudoku.box_of(idx);
+        for c in sudoku[idx] {
+            val_pos[c.trailing_zeros() as usize] |= 1 << box_i;
+        }
+    }
+    val_pos
+        .iter()
+        .enumerate()
+        .filter(|(_val_idx, val_pos_mask)| val_pos_mask.count_ones() == 1)
+        .filter_map(|(val_idx, val_pos_mask)| {
+            let box_i = val_pos_mask.trailing_zeros() as usize;
+            let eliminations: Vec<Candidate> = sudoku
+                .house(Unit::new(Grouping::Box, box_i))
+                .into_iter()
+                .filter(|i| {
+                    let (r, c, _b) = get_index_tuple(*i);
+                    match g {
+                        Grouping::Row => r != g_idx,
+                        Grouping::Column => c != g_idx,
+                        _ => true,
                     }
                 })
+                .filter(|i| !sudoku[*i].is_solved())
+                .flat_map(|i| Candidate::from_mask(i, sudoku[i].get() & (1 << val_idx)))
+                .collect();
+            if eliminations.is_empty() {
+                return None;
+            }
+            Some(Step {
+                technique: Technique::BoxLine,
+                units: vec![Unit::new(g, g_idx), Unit::new(Grouping::Box, box_i)],
+                cells: g
+                    .iter(g_idx)
+                    .filter(|i| sudoku[*i].get_candidates() & (1 << val_idx) != 0)
+                    .collect(),
+                digits: vec![val_idx + 1],
+                eliminations,
+                placements: vec![],
+                links: vec![],
+                sets: vec![],
             })
-            .unwrap_or((0, 0))
+        })
+        .collect()
+}
+
+impl LineBoxReduction for Sudoku {
+    fn find_box_line(&self) -> impl Iterator<Item = Step> + '_ {
+        [Grouping::Row, Grouping::Column]
+            .into_iter()
+            .flat_map(move |g| (0..9).flat_map(move |g_idx| box_line_in_line(self, g, g_idx)))
     }
 }
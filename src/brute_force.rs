@@ -0,0 +1,272 @@
+use crate::candidate_set::{CandidateSet, CandidateSetIterator};
+use crate::index::Grouping;
+use crate::killer::restrict_cages;
+use crate::step::Unit;
+use crate::sudoku::Sudoku;
+
+/// Solve by guessing instead of logic.
+///
+/// Every placement is propagated to its peers along with any
+/// naked or hidden singles that it leaves. When that stalls the
+/// unsolved cell with the fewest candidates, or the digit with the
+/// fewest places left in a house, is tried one at a time.
+pub trait BruteForce: Sized {
+    /// The first solution found, or None if there isn't one.
+    fn brute_force(&self) -> Option<Self>;
+}
+
+/// A grid of any size that the search can fill in.
+///
+/// Every position is a mask with a bit for each digit and the
+/// solved bit just above them, the same as `CandidateSet`.
+pub(crate) trait Grid: Clone {
+    /// How many digits, which is also how many positions are in a house.
+    fn digits(&self) -> usize;
+    fn mask(&self, i: usize) -> usize;
+    fn set_mask(&mut self, i: usize, m: usize);
+    /// Every position that can't hold the same digit as `i`.
+    /// It can include `i` and have positions more than once.
+    fn peers_of(&self, i: usize) -> Vec<usize>;
+    /// Every house that has to hold each digit once.
+    fn all_houses(&self) -> Vec<Vec<usize>>;
+    /// Take away candidates that rules other than houses rule out.
+    ///
+    /// Returns None if the grid is broken, otherwise if anything changed.
+    fn restrict(&mut self) -> Option<bool> {
+        Some(false)
+    }
+}
+
+impl<const N: usize> Grid for Sudoku<N> {
+    fn digits(&self) -> usize {
+        N
+    }
+
+    fn mask(&self, i: usize) -> usize {
+        self[i].get()
+    }
+
+    fn set_mask(&mut self, i: usize, m: usize) {
+        self[i] = CandidateSet::new(m);
+    }
+
+    fn peers_of(&self, i: usize) -> Vec<usize> {
+        [
+            Unit::new(Grouping::Row, i / N),
+            Unit::new(Grouping::Column, i % N),
+            Unit::new(Grouping::Box, self.box_of(i)),
+        ]
+        .into_iter()
+        .flat_map(|u| self.house(u))
+        .chain(self.regions_of(i).flat_map(|r| r.cells))
+        .chain(self.chess_peers(i))
+        .collect()
+    }
+
+    fn all_houses(&self) -> Vec<Vec<usize>> {
+        self.houses().map(|u| self.house(u).to_vec()).collect()
+    }
+
+    /// Killer cages can take away more candidates, which
+    /// might leave more singles.
+    fn restrict(&mut self) -> Option<bool> {
+        restrict_cages(self)
+    }
+}
+
+fn is_solved(grid: &impl Grid, i: usize) -> bool {
+    grid.mask(i) & 1 << grid.digits() != 0
+}
+
+/// Solve position `i` as the candidate `m` and remove `m` from all of
+/// its peers, following any naked singles that leaves.
+///
+/// Returns false if that breaks the puzzle.
+fn assign(grid: &mut impl Grid, i: usize, m: usize) -> bool {
+    let solved = 1 << grid.digits();
+    let mut todo = vec![(i, m)];
+    while let Some((i, m)) = todo.pop() {
+        if grid.mask(i) & m == 0 {
+            return false;
+        }
+        grid.set_mask(i, solved | m);
+        for p in grid.peers_of(i).into_iter().filter(|p| *p != i) {
+            let c = grid.mask(p);
+            if c & m == 0 {
+                continue;
+            }
+            if c & solved != 0 {
+                return false;
+            }
+            let left = c & !m;
+            match left.count_ones() {
+                0 => return false,
+                1 => todo.push((p, left)),
+                _ => {}
+            }
+            grid.set_mask(p, left);
+        }
+    }
+    true
+}
+
+/// Propagate every solved position and hidden single until nothing changes.
+///
+/// Returns false if the puzzle can't be solved.
+pub(crate) fn propagate(grid: &mut impl Grid) -> bool {
+    let all = (1 << grid.digits()) - 1;
+    for i in 0..grid.digits() * grid.digits() {
+        let single = is_solved(grid, i) || grid.mask(i).count_ones() == 1;
+        if single && !assign(grid, i, grid.mask(i) & all) {
+            return false;
+        }
+    }
+    // Every row, column, box, and extra region.
+    let houses = grid.all_houses();
+    loop {
+        let mut changed = false;
+        for cells in houses.iter() {
+            let mut once = 0;
+            let mut more = 0;
+            let mut solved = 0;
+            for i in cells.iter().copied() {
+                let c = grid.mask(i) & all;
+                if is_solved(grid, i) {
+                    solved |= c;
+                } else {
+                    more |= once & c;
+                    once |= c;
+                }
+            }
+            if (once | solved) != all {
+                return false;
+            }
+            // Digits that only have one place left in the unit.
+            for m in CandidateSetIterator::new(once & !more & !solved) {
+                // An earlier single in this unit may have taken its place.
+                let Some(i) = cells.iter().copied().find(|i| grid.mask(*i) & m != 0) else {
+                    return false;
+                };
+                if !assign(grid, i, m) {
+                    return false;
+                }
+                changed = true;
+            }
+        }
+        if !changed {
+            return true;
+        }
+    }
+}
+
+/// Hand every solution to `found` until it returns false.
+///
+/// Returns false once `found` has asked to stop.
+pub(crate) fn search<G: Grid>(grid: &G, found: &mut impl FnMut(&G) -> bool) -> bool {
+    let mut g = grid.clone();
+    loop {
+        if !propagate(&mut g) {
+            return true;
+        }
+        match g.restrict() {
+            None => return true,
+            Some(true) => {}
+            Some(false) => break,
+        }
+    }
+    match guesses(&g) {
+        None => found(&g),
+        Some(guesses) => guesses.into_iter().all(|(i, m)| {
+            let mut next = g.clone();
+            !assign(&mut next, i, m) || search(&next, found)
+        }),
+    }
+}
+
+/// The (position, candidate) pairs to try one at a time, or None if
+/// the puzzle is solved.
+///
+/// That's every candidate of the unsolved position with the fewest, or
+/// every place left for a digit in a house if there are fewer of those.
+/// Extra regions leave sparse puzzles with lots of positions that have
+/// many candidates, where a digit is usually quicker to pin down.
+fn guesses(grid: &impl Grid) -> Option<Vec<(usize, usize)>> {
+    let all = (1 << grid.digits()) - 1;
+    let fewest = (0..grid.digits() * grid.digits())
+        .filter(|i| !is_solved(grid, *i))
+        .min_by_key(|i| grid.mask(*i).count_ones())?;
+    let mut best: Vec<(usize, usize)> = CandidateSetIterator::new(grid.mask(fewest))
+        .map(|m| (fewest, m))
+        .collect();
+    // After propagating every digit has at least two places left.
+    if best.len() <= 2 {
+        return Some(best);
+    }
+    for cells in grid.all_houses() {
+        let solved = cells
+            .iter()
+            .filter(|i| is_solved(grid, **i))
+            .fold(0, |m, i| m | grid.mask(*i));
+        for m in CandidateSetIterator::new(all & !solved) {
+            let places: Vec<(usize, usize)> = cells
+                .iter()
+                .filter(|i| !is_solved(grid, **i) && grid.mask(**i) & m != 0)
+                .map(|i| (*i, m))
+                .collect();
+            if places.len() < best.len() {
+                best = places;
+            }
+        }
+    }
+    Some(best)
+}
+
+impl<const N: usize> BruteForce for Sudoku<N> {
+    fn brute_force(&self) -> Option<Self> {
+        let mut solution = None;
+        search(self, &mut |s| {
+            solution = Some(s.clone());
+            false
+        });
+        solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::*;
+    use crate::parse::*;
+
+    #[test]
+    fn test_brute_force_easy() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let solution = p.brute_force().unwrap();
+        assert!(solution.is_solved());
+        assert!(solution.is_valid());
+        assert_eq!(
+            "819637425527841369643529178476218953135796284298354716351962847764183592982475631",
+            solution.oneline()
+        );
+    }
+
+    #[test]
+    fn test_brute_force_lots() {
+        for x in PUZZLES.lines() {
+            let p = parse_sudoku(x).unwrap();
+            let solution = p.brute_force().unwrap();
+            assert!(solution.is_solved());
+            assert!(solution.is_valid());
+            // The givens are all still there.
+            assert!((0..81).all(|i| !p[i].is_solved() || p[i].get() == solution[i].get()));
+        }
+    }
+
+    #[test]
+    fn test_brute_force_no_solution() {
+        // Valid looking, but r1c8 and r1c9 can only be 9.
+        let s = "123456............................7.........8................8.........7.........";
+        let p = parse_sudoku(s).unwrap();
+        assert!(p.brute_force().is_none());
+    }
+}
@@ -4,29 +4,26 @@ use std::convert::From;
 use std::fmt;
 use std::iter::{IntoIterator, Iterator};
 
+/// The candidates left for a position, one bit for each of the `N`
+/// digits and a solved bit just above them.
 #[derive(Clone, Copy, PartialEq, Default)]
-pub struct CandidateSet(usize);
+pub struct CandidateSet<const N: usize = 9>(usize);
 
-// Constant with all the candidates turned on.
-const ALL_CAND: usize = 1 | 1 << 1 | 1 << 2 | 1 << 3 | 1 << 4 | 1 << 5 | 1 << 6 | 1 << 7 | 1 << 8;
+impl<const N: usize> CandidateSet<N> {
+    /// Every candidate turned on.
+    pub(crate) const ALL: usize = (1 << N) - 1;
+    pub(crate) const SOLVED: usize = 1 << N;
 
-const SOLVED: usize = 1 << 9;
-
-impl CandidateSet {
+    /// A digit, or an empty position as '0', '.', '*', or 'x'.
+    ///
+    /// The digits past 9 are letters starting from 'A' in either case.
     pub fn parse_position(c: char) -> Option<Self> {
-        // Use a match to get a really small lookup table based decoding.
         match c {
-            '1' => Some(CandidateSet::new(SOLVED | 1)),
-            '2' => Some(CandidateSet::new(SOLVED | 1 << 1)),
-            '3' => Some(CandidateSet::new(SOLVED | 1 << 2)),
-            '4' => Some(CandidateSet::new(SOLVED | 1 << 3)),
-            '5' => Some(CandidateSet::new(SOLVED | 1 << 4)),
-            '6' => Some(CandidateSet::new(SOLVED | 1 << 5)),
-            '7' => Some(CandidateSet::new(SOLVED | 1 << 6)),
-            '8' => Some(CandidateSet::new(SOLVED | 1 << 7)),
-            '9' => Some(CandidateSet::new(SOLVED | 1 << 8)),
-            '0' | '.' | '*' | 'x' => Some(CandidateSet::new(ALL_CAND)),
-            _ => None,
+            '0' | '.' | '*' | 'x' => Some(CandidateSet::new(Self::ALL)),
+            _ => match c.to_digit(36) {
+                Some(d) if d as usize <= N => Some(CandidateSet::new(Self::SOLVED | 1 << (d - 1))),
+                _ => None,
+            },
         }
     }
 
@@ -35,49 +32,49 @@ impl CandidateSet {
     }
 
     pub fn num_candidates(self) -> usize {
-        (self.0 & !SOLVED).count_ones() as usize
+        (self.0 & !Self::SOLVED).count_ones() as usize
     }
 
     pub fn is_solved(self) -> bool {
-        (self.0 & SOLVED) == SOLVED
+        (self.0 & Self::SOLVED) == Self::SOLVED
     }
     pub fn set_solved(&mut self) {
         debug_assert!(
             self.0.count_ones() == 1,
             "Only set solved on really solved sets."
         );
-        self.0 |= SOLVED;
+        self.0 |= Self::SOLVED;
     }
     pub fn value(self) -> Option<usize> {
         if self.is_solved() {
-            let v = (self.0 & !SOLVED).trailing_zeros() + 1;
+            let v = (self.0 & !Self::SOLVED).trailing_zeros() + 1;
             Some(v as usize)
         } else {
             None
         }
     }
     pub fn get_candidates(self) -> usize {
-        self.0 & !SOLVED
+        self.0 & !Self::SOLVED
     }
     pub fn get(self) -> usize {
         self.0
     }
 }
 
-impl Borrow<usize> for CandidateSet {
+impl<const N: usize> Borrow<usize> for CandidateSet<N> {
     fn borrow(&self) -> &usize {
         &self.0
     }
 }
 
-impl BorrowMut<usize> for CandidateSet {
+impl<const N: usize> BorrowMut<usize> for CandidateSet<N> {
     fn borrow_mut(&mut self) -> &mut usize {
         &mut self.0
     }
 }
 
-impl From<CandidateSet> for usize {
-    fn from(value: CandidateSet) -> Self {
+impl<const N: usize> From<CandidateSet<N>> for usize {
+    fn from(value: CandidateSet<N>) -> Self {
         value.0
     }
 }
@@ -107,19 +104,19 @@ impl Iterator for CandidateSetIterator {
     }
 }
 
-impl IntoIterator for CandidateSet {
+impl<const N: usize> IntoIterator for CandidateSet<N> {
     type Item = usize;
     type IntoIter = CandidateSetIterator;
     fn into_iter(self) -> CandidateSetIterator {
         debug_assert!(
-            self.get_candidates().count_ones() <= 9,
-            "We should always have 9 or fewer digits set."
+            self.get_candidates().count_ones() as usize <= N,
+            "We should always have N or fewer digits set."
         );
         CandidateSetIterator::new(self.get_candidates())
     }
 }
 
-impl fmt::Debug for CandidateSet {
+impl<const N: usize> fmt::Debug for CandidateSet<N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // For every mask convert it to the
         // possible value, convert that to
@@ -148,7 +145,7 @@ mod tests {
     fn test_new_numbers() {
         for i in 1..10 {
             let c = char::from_digit(i, 10).unwrap();
-            let p = CandidateSet::parse_position(c).unwrap();
+            let p: CandidateSet = CandidateSet::parse_position(c).unwrap();
             assert!(p.is_solved());
             if let Some(v) = p.value() {
                 // Values should be equal
@@ -163,13 +160,19 @@ mod tests {
 
     #[test]
     fn test_new_none() {
-        assert_eq!(None, CandidateSet::parse_position('/'));
+        assert_eq!(None, CandidateSet::<9>::parse_position('/'));
+        // Letters are only digits in bigger grids.
+        assert_eq!(None, CandidateSet::<9>::parse_position('A'));
+        let a = CandidateSet::<16>::parse_position('a').unwrap();
+        assert_eq!(Some(10), a.value());
+        assert_eq!(None, CandidateSet::<16>::parse_position('H'));
     }
 
     #[test]
     fn test_new_zero() {
         for c in ['.', '0', '*'].iter() {
-            assert_eq!(ALL_CAND, CandidateSet::parse_position(*c).unwrap().into());
+            let p: CandidateSet = CandidateSet::parse_position(*c).unwrap();
+            assert_eq!(CandidateSet::<9>::ALL, p.get());
         }
     }
 
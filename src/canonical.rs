@@ -0,0 +1,243 @@
+use crate::candidate_set::CandidateSet;
+use crate::remove_candidates::RemoveCandidates;
+use crate::sudoku::Sudoku;
+
+/// Puzzles that are the same up to relabelling the digits, reordering
+/// rows and columns without breaking up bands and stacks, and transposing.
+///
+/// http://sudopedia.enjoysudoku.com/Minlex_Form.html
+pub trait Canonical {
+    /// The givens of the puzzle arranged to be the lexicographically
+    /// smallest, with empty positions as 0, out of all 3,359,232
+    /// arrangements and every relabelling of the digits.
+    fn minlex(&self) -> Sudoku;
+    /// Can one puzzle be turned into the other.
+    fn is_isomorphic(&self, other: &Sudoku) -> bool;
+}
+
+/// A partly built arrangement.
+#[derive(Clone)]
+struct Arrangement {
+    /// The source grid, either the original or its transpose.
+    grid: usize,
+    /// Which source column goes in each column.
+    columns: [usize; 9],
+    /// Bitmask of the bands already used.
+    bands: usize,
+    /// The band being filled in, and the bitmask of its rows used so far.
+    band: usize,
+    rows: usize,
+    /// The new label of each digit, 0 if it hasn't been seen yet.
+    labels: [u8; 10],
+    next_label: u8,
+}
+
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// All 1,296 ways of ordering the columns keeping the stacks together.
+fn column_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(1296);
+    for stacks in PERMUTATIONS {
+        for a in PERMUTATIONS {
+            for b in PERMUTATIONS {
+                for c in PERMUTATIONS {
+                    let mut order = [0; 9];
+                    for (n, within) in [a, b, c].into_iter().enumerate() {
+                        for k in 0..3 {
+                            order[n * 3 + k] = stacks[n] * 3 + within[k];
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// The value of each given, 0 for everything else.
+fn givens(sudoku: &Sudoku) -> [u8; 81] {
+    let mut digits = [0; 81];
+    for (i, d) in digits.iter_mut().enumerate() {
+        *d = sudoku[i].value().unwrap_or(0) as u8;
+    }
+    digits
+}
+
+fn transpose(digits: &[u8; 81]) -> [u8; 81] {
+    let mut t = [0; 81];
+    for (i, d) in t.iter_mut().enumerate() {
+        *d = digits[(i % 9) * 9 + i / 9];
+    }
+    t
+}
+
+/// The smallest arrangement of the givens, found one row at a time.
+///
+/// Every arrangement whose rows so far tie for the smallest is kept,
+/// along with the labels it has given to the digits, and extended
+/// with each row that could come next.
+fn minlex_digits(digits: &[u8; 81]) -> [u8; 81] {
+    let grids = [*digits, transpose(digits)];
+    let mut arrangements: Vec<Arrangement> = (0..2)
+        .flat_map(|grid| {
+            column_orders().into_iter().map(move |columns| Arrangement {
+                grid,
+                columns,
+                bands: 0,
+                band: 0,
+                rows: 0,
+                labels: [0; 10],
+                next_label: 1,
+            })
+        })
+        .collect();
+    let mut result = [0; 81];
+    for k in 0..9 {
+        let mut best: Option<[u8; 9]> = None;
+        let mut next = vec![];
+        for a in arrangements.iter() {
+            // The first row of a band can come from any band not used yet.
+            let choices: Vec<(usize, usize)> = if k % 3 == 0 {
+                (0..3)
+                    .filter(|b| a.bands & (1 << b) == 0)
+                    .flat_map(|b| (0..3).map(move |r| (b, r)))
+                    .collect()
+            } else {
+                (0..3)
+                    .filter(|r| a.rows & (1 << r) == 0)
+                    .map(|r| (a.band, r))
+                    .collect()
+            };
+            for (band, r) in choices {
+                let mut n = a.clone();
+                if k % 3 == 0 {
+                    n.bands |= 1 << band;
+                    n.band = band;
+                    n.rows = 0;
+                }
+                n.rows |= 1 << r;
+                let source = &grids[a.grid][(band * 3 + r) * 9..];
+                let mut row = [0; 9];
+                for (c, v) in row.iter_mut().enumerate() {
+                    let d = source[n.columns[c]] as usize;
+                    if d != 0 && n.labels[d] == 0 {
+                        n.labels[d] = n.next_label;
+                        n.next_label += 1;
+                    }
+                    *v = n.labels[d];
+                }
+                match best {
+                    Some(b) if row > b => continue,
+                    Some(b) if row == b => {}
+                    _ => {
+                        best = Some(row);
+                        next.clear();
+                    }
+                }
+                next.push(n);
+            }
+        }
+        result[k * 9..k * 9 + 9].copy_from_slice(&best.unwrap());
+        arrangements = next;
+    }
+    result
+}
+
+impl Canonical for Sudoku {
+    fn minlex(&self) -> Sudoku {
+        let digits = minlex_digits(&givens(self));
+        let mut positions = [CandidateSet::new((1 << 9) - 1); 81];
+        for (p, d) in positions.iter_mut().zip(digits) {
+            if d != 0 {
+                *p = CandidateSet::new(1 << (d - 1));
+                p.set_solved();
+            }
+        }
+        let mut s = Sudoku::new(positions);
+        s.remove_candidates(false);
+        s
+    }
+
+    fn is_isomorphic(&self, other: &Sudoku) -> bool {
+        self.num_solved() == other.num_solved()
+            && minlex_digits(&givens(self)) == minlex_digits(&givens(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::*;
+    use crate::parse::*;
+
+    /// Swap the digits around, swap the first two bands,
+    /// reverse the columns of the last stack, and transpose.
+    fn shuffle(s: &str) -> String {
+        let relabel = |c: char| match c.to_digit(10) {
+            Some(d) => char::from_digit(d % 9 + 1, 10).unwrap(),
+            None => '.',
+        };
+        let chars: Vec<char> = s.chars().map(relabel).collect();
+        let mut out = vec!['.'; 81];
+        for row in 0..9 {
+            for col in 0..9 {
+                let from_row = match row / 3 {
+                    0 => row + 3,
+                    1 => row - 3,
+                    _ => row,
+                };
+                let from_col = if col >= 6 { 14 - col } else { col };
+                out[col * 9 + row] = chars[from_row * 9 + from_col];
+            }
+        }
+        out.into_iter().collect()
+    }
+
+    #[test]
+    fn test_minlex_solved_grid() {
+        // The smallest possible grid is already in minlex form.
+        let s = "123456789456789123789123456214365897365897214897214365531642978642978531978531642";
+        let p = parse_sudoku(&shuffle(s)).unwrap();
+        assert_ne!(s, p.oneline());
+        let m = p.minlex();
+        assert!(m.is_solved() && m.is_valid());
+        assert_eq!(s, m.oneline());
+    }
+
+    #[test]
+    fn test_minlex_is_canonical() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let q = parse_sudoku(&shuffle(ONE_LINE)).unwrap();
+        let m = p.minlex();
+        assert_eq!(m.oneline(), q.minlex().oneline());
+        assert_eq!(m.oneline(), m.minlex().oneline());
+        assert_eq!(p.num_solved(), m.num_solved());
+        // Empty positions sort first so the top left is empty.
+        assert!(!m[0].is_solved());
+        // The minlex form has the same solution count.
+        assert!(m.has_unique_solution());
+    }
+
+    #[test]
+    fn test_is_isomorphic() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let q = parse_sudoku(&shuffle(&shuffle(ONE_LINE))).unwrap();
+        assert!(p.is_isomorphic(&q));
+        assert!(q.is_isomorphic(&p));
+        let h = parse_sudoku(HARD).unwrap();
+        assert!(!p.is_isomorphic(&h));
+        for (a, b) in PUZZLES.lines().zip(PUZZLES.lines().skip(1)) {
+            let a = parse_sudoku(a).unwrap();
+            let b = parse_sudoku(b).unwrap();
+            assert!(!a.is_isomorphic(&b));
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use crate::index::Grouping;
+use crate::step::{Candidate, Link, Node, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+use std::collections::{HashMap, VecDeque};
+
+pub trait Chains {
+    fn find_x_chain(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_xy_chain(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_aic(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// Which links a graph is allowed to use.
+#[derive(Debug, Clone, Copy)]
+struct ChainKind {
+    technique: Technique,
+    /// Strong links from a digit only having two places in a unit.
+    unit_links: bool,
+    /// Strong links from a cell only having two digits, and weak
+    /// links between the digits of a cell.
+    cell_links: bool,
+    /// Nodes made from a box/line intersection.
+    groups: bool,
+}
+
+const X_CHAIN: ChainKind = ChainKind {
+    technique: Technique::XChain,
+    unit_links: true,
+    cell_links: false,
+    groups: false,
+};
+
+const XY_CHAIN: ChainKind = ChainKind {
+    technique: Technique::XYChain,
+    unit_links: false,
+    cell_links: true,
+    groups: false,
+};
+
+const AIC: ChainKind = ChainKind {
+    technique: Technique::AlternatingInferenceChain,
+    unit_links: true,
+    cell_links: true,
+    groups: true,
+};
+
+/// Every candidate (and group) with the links between them.
+///
+/// A strong link means that if one end is false the other is true.
+/// A weak link means if one end is true the other is false.
+struct Graph {
+    nodes: Vec<Node>,
+    strong: Vec<Vec<usize>>,
+    weak: Vec<Vec<usize>>,
+}
+
+/// A bit mask of the cells in `cells`.
+fn cell_mask(cells: &[usize]) -> u128 {
+    cells.iter().fold(0, |a, i| a | (1 << i))
+}
+
+/// Unsolved positions in unit `g`/`g_idx` with digit `m`,
+/// or nothing if the digit is already solved in the unit.
+fn unit_cells(sudoku: &Sudoku, g: Grouping, g_idx: usize, m: usize) -> Vec<usize> {
+    let mut cells = Vec::new();
+    for i in sudoku.house(Unit::new(g, g_idx)) {
+        if sudoku[i].get_candidates() & m != 0 {
+            if sudoku[i].is_solved() {
+                return vec![];
+            }
+            cells.push(i);
+        }
+    }
+    cells
+}
+
+impl Graph {
+    fn new(sudoku: &Sudoku, kind: ChainKind) -> Self {
+        let mut nodes = Vec::new();
+        for i in (0..81).filter(|i| !sudoku[*i].is_solved()) {
+            nodes.extend(Candidate::from_mask(i, sudoku[i].get()).map(Node::from));
+        }
+        if kind.groups {
+            // Cells where a box crosses a row or column.
+            for box_i in 0..9 {
+                let box_cells = sudoku.house(Unit::new(Grouping::Box, box_i));
+                for g in [Grouping::Row, Grouping::Column] {
+                    for line in 0..9 {
+                        for digit in 1..10 {
+                            let cells: Vec<usize> = box_cells
+                                .into_iter()
+                                .filter(|i| g.iter(line).any(|l| l == *i))
+                                .filter(|i| !sudoku[*i].is_solved())
+                                .filter(|i| sudoku[*i].get() & (1 << (digit - 1)) != 0)
+                                .collect();
+                            if cells.len() > 1 {
+                                nodes.push(Node { cells, digit });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let lookup: HashMap<(usize, u128), usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(n, node)| ((node.digit, cell_mask(&node.cells)), n))
+            .collect();
+        let mut strong = vec![Vec::new(); nodes.len()];
+        let mut weak = vec![Vec::new(); nodes.len()];
+
+        if kind.unit_links {
+            // If a digit in a unit is split between two nodes
+            // then one of them has to be true.
+            for u in sudoku.houses() {
+                for digit in 1..10 {
+                    let in_unit =
+                        cell_mask(&unit_cells(sudoku, u.grouping, u.index, 1 << (digit - 1)));
+                    for (n, node) in nodes.iter().enumerate() {
+                        let m = cell_mask(&node.cells);
+                        if node.digit != digit || m & !in_unit != 0 || m == in_unit {
+                            continue;
+                        }
+                        if let Some(other) = lookup.get(&(digit, in_unit & !m)) {
+                            if !strong[n].contains(other) {
+                                strong[n].push(*other);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if kind.cell_links {
+            for i in (0..81).filter(|i| !sudoku[*i].is_solved()) {
+                let here: Vec<usize> = Candidate::from_mask(i, sudoku[i].get())
+                    .map(|c| lookup[&(c.digit, 1 << i)])
+                    .collect();
+                for a in here.iter() {
+                    for b in here.iter().filter(|b| *b != a) {
+                        if here.len() == 2 {
+                            strong[*a].push(*b);
+                        }
+                        weak[*a].push(*b);
+                    }
+                }
+            }
+        }
+        // The same digit can't be true in two nodes that see each other.
+        for (a, node_a) in nodes.iter().enumerate() {
+            for (b, node_b) in nodes.iter().enumerate() {
+                if node_a.digit == node_b.digit
+                    && node_a
+                        .cells
+                        .iter()
+                        .all(|x| node_b.cells.iter().all(|y| sudoku.sees(*x, *y)))
+                {
+                    weak[a].push(b);
+                }
+            }
+        }
+        Graph {
+            nodes,
+            strong,
+            weak,
+        }
+    }
+
+    /// The candidates that are removed if either `a` or `b` is true.
+    fn eliminations(&self, sudoku: &Sudoku, a: usize, b: usize) -> Vec<Candidate> {
+        let (a, b) = (&self.nodes[a], &self.nodes[b]);
+        if a.digit == b.digit {
+            let ends = [a.cells.clone(), b.cells.clone()].concat();
+            return (0..81)
+                .filter(|i| !ends.contains(i) && !sudoku[*i].is_solved())
+                .filter(|i| ends.iter().all(|e| sudoku.sees(*i, *e)))
+                .flat_map(|i| Candidate::from_mask(i, sudoku[i].get() & (1 << (a.digit - 1))))
+                .collect();
+        }
+        if a.cells.len() > 1 || b.cells.len() > 1 {
+            return vec![];
+        }
+        let (a_cell, b_cell) = (a.cells[0], b.cells[0]);
+        let pair = (1 << (a.digit - 1)) | (1 << (b.digit - 1));
+        if a_cell == b_cell {
+            // One of the two digits is the solution.
+            Candidate::from_mask(a_cell, sudoku[a_cell].get_candidates() & !pair).collect()
+        } else if sudoku.sees(a_cell, b_cell) {
+            // Either end being true removes the other end's digit
+            // from the other cell.
+            Candidate::from_mask(a_cell, sudoku[a_cell].get() & (1 << (b.digit - 1)))
+                .chain(Candidate::from_mask(
+                    b_cell,
+                    sudoku[b_cell].get() & (1 << (a.digit - 1)),
+                ))
+                .collect()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Search out from `start` for chains that begin and end with a
+    /// strong link, shortest first.
+    ///
+    /// States are (node, is the node true); `start` is assumed false.
+    fn chains_from(&self, sudoku: &Sudoku, kind: ChainKind, start: usize) -> Vec<Step> {
+        let n = self.nodes.len();
+        let state = |node: usize, on: bool| node * 2 + on as usize;
+        let mut parent: Vec<Option<usize>> = vec![None; n * 2];
+        let mut seen = vec![false; n * 2];
+        let mut queue = VecDeque::new();
+        seen[state(start, false)] = true;
+        queue.push_back(state(start, false));
+        let mut steps = Vec::new();
+
+        while let Some(cur) = queue.pop_front() {
+            let (node, on) = (cur / 2, cur % 2 == 1);
+            let path = self.path(&parent, cur);
+            let next = if on {
+                &self.weak[node]
+            } else {
+                &self.strong[node]
+            };
+            for m in next.iter() {
+                let s = state(*m, !on);
+                // Keep each node in the chain once.
+                if seen[s] || path.iter().any(|p| p / 2 == *m) {
+                    continue;
+                }
+                seen[s] = true;
+                parent[s] = Some(cur);
+                queue.push_back(s);
+                // Need at least two strong links to make a chain.
+                if on || path.len() < 3 {
+                    continue;
+                }
+                let eliminations = self.eliminations(sudoku, start, *m);
+                if eliminations.is_empty() {
+                    continue;
+                }
+                let mut chain = path.clone();
+                chain.push(s);
+                steps.push(self.chain_step(kind, &chain, eliminations));
+            }
+        }
+        steps
+    }
+
+    /// Follow the parents back to the start of the chain.
+    fn path(&self, parent: &[Option<usize>], mut cur: usize) -> Vec<usize> {
+        let mut path = vec![cur];
+        while let Some(p) = parent[cur] {
+            path.push(p);
+            cur = p;
+        }
+        path.reverse();
+        path
+    }
+
+    fn chain_step(&self, kind: ChainKind, chain: &[usize], eliminations: Vec<Candidate>) -> Step {
+        let links: Vec<Link> = chain
+            .windows(2)
+            .map(|w| {
+                let from = self.nodes[w[0] / 2].clone();
+                let to = self.nodes[w[1] / 2].clone();
+                // Moving to a true node means the link was strong.
+                Link::new(from, to, w[1] % 2 == 1)
+            })
+            .collect();
+        let mut cells: Vec<usize> = Vec::new();
+        let mut digits: Vec<usize> = Vec::new();
+        for node in chain.iter().map(|s| &self.nodes[s / 2]) {
+            for i in node.cells.iter() {
+                if !cells.contains(i) {
+                    cells.push(*i);
+                }
+            }
+            if !digits.contains(&node.digit) {
+                digits.push(node.digit);
+            }
+        }
+        digits.sort();
+        Step {
+            technique: kind.technique,
+            units: vec![],
+            cells,
+            digits,
+            eliminations,
+            placements: vec![],
+            links,
+            sets: vec![],
+        }
+    }
+}
+
+fn find_chains(sudoku: &Sudoku, kind: ChainKind) -> impl Iterator<Item = Step> + '_ {
+    let graph = Graph::new(sudoku, kind);
+    (0..graph.nodes.len()).flat_map(move |start| graph.chains_from(sudoku, kind, start))
+}
+
+impl Chains for Sudoku {
+    fn find_x_chain(&self) -> impl Iterator<Item = Step> + '_ {
+        find_chains(self, X_CHAIN)
+    }
+
+    fn find_xy_chain(&self) -> impl Iterator<Item = Step> + '_ {
+        find_chains(self, XY_CHAIN)
+    }
+
+    fn find_aic(&self) -> impl Iterator<Item = Step> + '_ {
+        find_chains(self, AIC)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_helpers::to_index;
+    use crate::test_helpers::step_until;
+
+    /// Chains start and end with a strong link and alternate in between.
+    fn assert_alternates(step: &Step) {
+        assert_eq!(1, step.links.len() % 2);
+        for (n, link) in step.links.iter().enumerate() {
+            assert_eq!(n % 2 == 0, link.strong);
+        }
+        for w in step.links.windows(2) {
+            assert_eq!(w[0].to, w[1].from);
+        }
+    }
+
+    #[test]
+    fn test_x_chain() {
+        let s = "......5.6.......719.7.....3..67....2.5..98.1...456.8...2..7......9.34........6..8";
+        let (_, step) = step_until(s, Technique::XChain);
+        assert_alternates(&step);
+        assert_eq!(vec![9], step.digits);
+        assert_eq!(vec![Candidate::new(to_index(5, 7), 9)], step.eliminations);
+    }
+
+    #[test]
+    fn test_xy_chain() {
+        let s = ".....2.87...6....48...3..5.5.4.......3....2....7....612.....5....14...7......5.2.";
+        let (_, step) = step_until(s, Technique::XYChain);
+        assert_alternates(&step);
+        // Every strong link is inside a bi-value cell.
+        assert!(step
+            .links
+            .iter()
+            .filter(|l| l.strong)
+            .all(|l| l.from.cells == l.to.cells));
+        assert_eq!(vec![Candidate::new(to_index(1, 7), 3)], step.eliminations);
+    }
+
+    #[test]
+    fn test_grouped_aic() {
+        let s = "2.....36..7...31..8..9....5..4.6..29.1.4.....6...3.......1...5.....94..6.6...8...";
+        let (_, step) = step_until(s, Technique::AlternatingInferenceChain);
+        assert_alternates(&step);
+        assert_eq!("(7)r6c3=(7)r5c13", step.links[0].to_string());
+        assert_eq!(vec![Candidate::new(to_index(6, 2), 7)], step.eliminations);
+    }
+}
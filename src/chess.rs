@@ -0,0 +1,157 @@
+use crate::index_helpers::{get_index_tuple, to_index};
+
+/// A chess piece's move. Positions that are a move apart can't
+/// hold the same digit, so they see each other like they share
+/// a house.
+///
+/// https://en.wikipedia.org/wiki/Miracle_Sudoku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChessMove {
+    /// Two positions one way and one the other, as in Anti-Knight Sudoku.
+    Knight,
+    /// One position in any direction, as in Anti-King Sudoku.
+    King,
+}
+
+impl ChessMove {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            ChessMove::Knight => &[
+                (-2, -1),
+                (-2, 1),
+                (-1, -2),
+                (-1, 2),
+                (1, -2),
+                (1, 2),
+                (2, -1),
+                (2, 1),
+            ],
+            ChessMove::King => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+
+    /// The positions one move away from `i`.
+    pub fn reach(self, i: usize) -> impl Iterator<Item = usize> {
+        let (row, col, _) = get_index_tuple(i);
+        self.offsets().iter().filter_map(move |(dr, dc)| {
+            let r = row.checked_add_signed(*dr).filter(|r| *r < 9)?;
+            let c = col.checked_add_signed(*dc).filter(|c| *c < 9)?;
+            Some(to_index(r, c))
+        })
+    }
+
+    /// Are `a` and `b` one move apart.
+    pub fn connects(self, a: usize, b: usize) -> bool {
+        let (row_a, col_a, _) = get_index_tuple(a);
+        let (row_b, col_b, _) = get_index_tuple(b);
+        let dr = row_a.abs_diff(row_b);
+        let dc = col_a.abs_diff(col_b);
+        match self {
+            ChessMove::Knight => (dr == 1 && dc == 2) || (dr == 2 && dc == 1),
+            ChessMove::King => dr <= 1 && dc <= 1 && a != b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SudokuErr;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+    use crate::transform::Transform;
+
+    #[test]
+    fn test_reach() {
+        let corner: Vec<usize> = ChessMove::Knight.reach(0).collect();
+        assert_eq!(vec![11, 19], corner);
+        assert_eq!(8, ChessMove::Knight.reach(40).count());
+        let king: Vec<usize> = ChessMove::King.reach(80).collect();
+        assert_eq!(vec![70, 71, 79], king);
+        for m in [ChessMove::Knight, ChessMove::King] {
+            for i in 0..81 {
+                assert!(m.reach(i).all(|p| m.connects(i, p)));
+                assert_eq!(
+                    m.reach(i).count(),
+                    (0..81).filter(|p| m.connects(i, *p)).count()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_anti_knight() {
+        assert_eq!(2, parse_sudoku(ANTI_KNIGHT).unwrap().count_solutions(2));
+        let classic = parse_sudoku(ANTI_KNIGHT).unwrap();
+        let p = parse_sudoku_with_chess(ANTI_KNIGHT, vec![ChessMove::Knight]).unwrap();
+        // r1c5 is a 5 so r2c3 and r3c6 can't be.
+        assert!(classic[11].get_candidates() & 1 << 4 != 0);
+        assert_eq!(0, p[11].get_candidates() & 1 << 4);
+        assert_eq!(0, p[23].get_candidates() & 1 << 4);
+        assert!(p.has_unique_solution());
+        let sr = p.try_solve();
+        assert!(sr.is_solved && !sr.guessed);
+        assert_eq!(
+            "123456789987312456465798213251983674734621598896574132512849367378265941649137825",
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_anti_king() {
+        assert_eq!(2, parse_sudoku(ANTI_KING).unwrap().count_solutions(2));
+        let p = parse_sudoku_with_chess(ANTI_KING, vec![ChessMove::King]).unwrap();
+        assert!(p.has_unique_solution());
+        let sr = p.try_solve();
+        assert!(sr.is_solved && !sr.guessed);
+        assert_eq!(
+            "123456789785293146469187235812534697374962518596718324641825973937641852258379461",
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_invalid_chess() {
+        // Two 1s a knight's move apart at r1c3 and r2c5.
+        let pzl = format!("..1{}1{}", ".".repeat(10), ".".repeat(67));
+        assert!(parse_sudoku(&pzl).is_ok());
+        assert!(matches!(
+            parse_sudoku_with_chess(&pzl, vec![ChessMove::Knight]),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+        assert!(parse_sudoku_with_chess(&pzl, vec![ChessMove::King]).is_ok());
+        // This grid has 3s a knight's move apart at r1c5 and r2c7.
+        let s = "819637425527841369643529178476218953135796284298354716351962847764183592982475631";
+        let mut p = parse_sudoku(s).unwrap();
+        assert!(p.is_valid());
+        p.set_chess(vec![ChessMove::Knight]);
+        assert!(!p.is_valid());
+    }
+
+    #[test]
+    fn test_transform_keeps_chess() {
+        let mut p = parse_sudoku_with_chess(ANTI_KNIGHT, vec![ChessMove::Knight]).unwrap();
+        p.rotate();
+        p.transpose();
+        assert_eq!(&[ChessMove::Knight], p.chess());
+        assert!(p.is_valid());
+        assert!(p.has_unique_solution());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_bands_chess() {
+        let mut p = parse_sudoku_with_chess(ANTI_KING, vec![ChessMove::King]).unwrap();
+        p.swap_bands(0, 1);
+    }
+}
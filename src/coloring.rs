@@ -0,0 +1,273 @@
+use crate::single_digit::conjugate_pair;
+use crate::step::{Candidate, Link, Step, Technique};
+use crate::sudoku::Sudoku;
+
+pub trait Coloring {
+    fn find_simple_coloring(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_multi_coloring(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// A group of positions joined by conjugate pairs for one digit.
+/// Every position gets one of two colors; either all of one
+/// color hold the digit or all of the other do.
+#[derive(Debug)]
+struct Cluster {
+    /// The positions of each color.
+    colors: [Vec<usize>; 2],
+    /// The conjugate pairs that join the cluster.
+    links: Vec<Link>,
+}
+
+impl Cluster {
+    fn contains(&self, i: usize) -> bool {
+        self.colors.iter().any(|c| c.contains(&i))
+    }
+
+    fn cells(&self) -> Vec<usize> {
+        self.colors.concat()
+    }
+}
+
+/// Does any position in `cells` see `i`.
+fn any_sees(sudoku: &Sudoku, cells: &[usize], i: usize) -> bool {
+    cells.iter().any(|c| sudoku.sees(*c, i))
+}
+
+/// Build the two colored clusters for the digit `val_idx`.
+fn clusters(sudoku: &Sudoku, val_idx: usize) -> Vec<Cluster> {
+    let m = 1 << val_idx;
+    let mut pairs: Vec<[usize; 2]> = sudoku
+        .houses()
+        .filter_map(|u| conjugate_pair(sudoku, u.grouping, u.index, m))
+        .collect();
+    // A pair in the same row and box only needs to be counted once.
+    pairs.sort();
+    pairs.dedup();
+
+    let mut color: [Option<(usize, usize)>; 81] = [None; 81];
+    let mut clusters = Vec::new();
+    for start in pairs.iter().map(|p| p[0]) {
+        if color[start].is_some() {
+            continue;
+        }
+        let id = clusters.len();
+        let mut cluster = Cluster {
+            colors: [vec![start], vec![]],
+            links: vec![],
+        };
+        color[start] = Some((id, 0));
+        let mut queue = vec![start];
+        while let Some(cur) = queue.pop() {
+            let (_, cur_color) = color[cur].unwrap();
+            for pair in pairs.iter().filter(|p| p.contains(&cur)) {
+                let other = if pair[0] == cur { pair[1] } else { pair[0] };
+                if color[other].is_none() {
+                    color[other] = Some((id, 1 - cur_color));
+                    cluster.colors[1 - cur_color].push(other);
+                    cluster.links.push(Link::new(
+                        Candidate::new(cur, val_idx + 1),
+                        Candidate::new(other, val_idx + 1),
+                        true,
+                    ));
+                    queue.push(other);
+                }
+            }
+        }
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+fn coloring_step(
+    technique: Technique,
+    cells: Vec<usize>,
+    links: Vec<Link>,
+    eliminations: Vec<Candidate>,
+    val_idx: usize,
+) -> Option<Step> {
+    if eliminations.is_empty() {
+        None
+    } else {
+        Some(Step {
+            technique,
+            units: vec![],
+            cells,
+            digits: vec![val_idx + 1],
+            eliminations,
+            placements: vec![],
+            links,
+            sets: vec![],
+        })
+    }
+}
+
+/// Unsolved positions outside the clusters that still have the digit.
+fn uncolored<'a>(
+    sudoku: &'a Sudoku,
+    clusters: &'a [&Cluster],
+    m: usize,
+) -> impl Iterator<Item = usize> + 'a {
+    (0..81).filter(move |i| {
+        !sudoku[*i].is_solved()
+            && sudoku[*i].get() & m != 0
+            && !clusters.iter().any(|c| c.contains(*i))
+    })
+}
+
+fn simple_coloring_steps(sudoku: &Sudoku, val_idx: usize) -> Vec<Step> {
+    let m = 1 << val_idx;
+    clusters(sudoku, val_idx)
+        .into_iter()
+        .filter_map(|cluster| {
+            // Color wrap: if two positions of the same color see each other,
+            // that color can't be the digit.
+            let wrap = cluster.colors.iter().find(|color| {
+                color
+                    .iter()
+                    .enumerate()
+                    .any(|(n, a)| color[n + 1..].iter().any(|b| sudoku.sees(*a, *b)))
+            });
+            if let Some(color) = wrap {
+                let eliminations = color
+                    .iter()
+                    .map(|i| Candidate::new(*i, val_idx + 1))
+                    .collect();
+                return coloring_step(
+                    Technique::ColorWrap,
+                    cluster.cells(),
+                    cluster.links.clone(),
+                    eliminations,
+                    val_idx,
+                );
+            }
+            // Color trap: anything that sees both colors can't be the digit.
+            let eliminations = uncolored(sudoku, &[&cluster], m)
+                .filter(|i| cluster.colors.iter().all(|c| any_sees(sudoku, c, *i)))
+                .map(|i| Candidate::new(i, val_idx + 1))
+                .collect();
+            coloring_step(
+                Technique::ColorTrap,
+                cluster.cells(),
+                cluster.links.clone(),
+                eliminations,
+                val_idx,
+            )
+        })
+        .collect()
+}
+
+fn multi_coloring_steps(sudoku: &Sudoku, val_idx: usize) -> Vec<Step> {
+    let m = 1 << val_idx;
+    let clusters = clusters(sudoku, val_idx);
+    let mut steps = Vec::new();
+    for (n, first) in clusters.iter().enumerate() {
+        for second in clusters.iter().skip(n + 1) {
+            let cells = [first.cells(), second.cells()].concat();
+            let links = [first.links.clone(), second.links.clone()].concat();
+            for a in 0..2 {
+                for b in 0..2 {
+                    let a_cells = &first.colors[a];
+                    let b_cells = &second.colors[b];
+                    if !a_cells.iter().any(|i| any_sees(sudoku, b_cells, *i)) {
+                        continue;
+                    }
+                    // Color a and color b can't both be true so one of
+                    // their opposites is. Anything seeing both opposites goes.
+                    let a_opposite = &first.colors[1 - a];
+                    let b_opposite = &second.colors[1 - b];
+                    let eliminations: Vec<Candidate> = uncolored(sudoku, &[first, second], m)
+                        .filter(|i| {
+                            any_sees(sudoku, a_opposite, *i) && any_sees(sudoku, b_opposite, *i)
+                        })
+                        .map(|i| Candidate::new(i, val_idx + 1))
+                        .collect();
+                    steps.extend(coloring_step(
+                        Technique::MultiColoring,
+                        cells.clone(),
+                        links.clone(),
+                        eliminations,
+                        val_idx,
+                    ));
+                    // If color a sees both colors of the second cluster
+                    // then it can't be true.
+                    if a_cells.iter().any(|i| any_sees(sudoku, b_opposite, *i)) {
+                        let eliminations = a_cells
+                            .iter()
+                            .map(|i| Candidate::new(*i, val_idx + 1))
+                            .collect();
+                        steps.extend(coloring_step(
+                            Technique::MultiColoring,
+                            cells.clone(),
+                            links.clone(),
+                            eliminations,
+                            val_idx,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    steps
+}
+
+impl Coloring for Sudoku {
+    fn find_simple_coloring(&self) -> impl Iterator<Item = Step> + '_ {
+        (0..9).flat_map(move |val_idx| simple_coloring_steps(self, val_idx))
+    }
+
+    fn find_multi_coloring(&self) -> impl Iterator<Item = Step> + '_ {
+        (0..9).flat_map(move |val_idx| multi_coloring_steps(self, val_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_helpers::to_index;
+    use crate::test_helpers::{step_until, with_ones};
+
+    #[test]
+    fn test_color_wrap() {
+        // r1c1=r1c5=r4c5=r4c2=r2c2 colors r1c1 and r2c2 the same,
+        // but they're both in box 1.
+        let p = with_ones(&[
+            to_index(0, 0),
+            to_index(0, 4),
+            to_index(3, 4),
+            to_index(3, 1),
+            to_index(1, 1),
+            to_index(2, 2),
+        ]);
+        let step = p.find_simple_coloring().next().unwrap();
+        assert_eq!(Technique::ColorWrap, step.technique);
+        assert_eq!(4, step.links.len());
+        let mut removed: Vec<usize> = step.eliminations.iter().map(|c| c.index).collect();
+        removed.sort();
+        assert_eq!(
+            vec![to_index(0, 0), to_index(1, 1), to_index(3, 4)],
+            removed
+        );
+    }
+
+    #[test]
+    fn test_color_trap() {
+        let s = "4.......8...7.3.....9...21...4..176..58..7..4.6.5....22...7...63..8.2....4...6...";
+        let (_, step) = step_until(s, Technique::ColorTrap);
+        assert_eq!(vec![5], step.digits);
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(0, 4), 5),
+                Candidate::new(to_index(1, 4), 5)
+            ],
+            step.eliminations
+        );
+    }
+
+    #[test]
+    fn test_multi_coloring() {
+        let s = "......2744.9...61...........4.7.1..9.236........28.....7.1.6.958.....1..1....8.62";
+        let (_, step) = step_until(s, Technique::MultiColoring);
+        assert_eq!(vec![3], step.digits);
+        assert_eq!(vec![Candidate::new(to_index(7, 1), 3)], step.eliminations);
+    }
+}
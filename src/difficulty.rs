@@ -0,0 +1,285 @@
+use crate::solve::SolveReport;
+use crate::step::Technique;
+use std::fmt;
+
+/// A rough bucket for how hard a puzzle is, based on the
+/// hardest technique needed to solve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Only hidden and naked singles. Filling in hidden singles
+    /// always leaves some naked singles, like the last empty
+    /// position in a unit, so they can't be told apart.
+    Beginner,
+    /// Locked candidates and Killer cage combinations.
+    Easy,
+    /// Naked and hidden subsets, and Killer innies and outies.
+    Medium,
+    /// Fish, single digit patterns, wings, and uniqueness.
+    Hard,
+    /// Coloring, X-Chains, and XY-Chains.
+    Expert,
+    /// AICs, Almost Locked Sets, or more than the techniques can do.
+    Extreme,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+            Difficulty::Extreme => "Extreme",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Technique {
+    /// The difficulty bucket the technique belongs to.
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Technique::HiddenSingle | Technique::NakedSingle => Difficulty::Beginner,
+            Technique::Pointing | Technique::BoxLine | Technique::CageCombination => {
+                Difficulty::Easy
+            }
+            Technique::NakedSubset(_)
+            | Technique::HiddenSubset(_)
+            | Technique::Innies
+            | Technique::Outies => Difficulty::Medium,
+            Technique::Fish(_)
+            | Technique::FinnedFish(_)
+            | Technique::SashimiFish(_)
+            | Technique::Skyscraper
+            | Technique::TwoStringKite
+            | Technique::TurbotFish
+            | Technique::EmptyRectangle
+            | Technique::XYWing
+            | Technique::XYZWing
+            | Technique::UniqueRectangle(_)
+            | Technique::HiddenUniqueRectangle
+            | Technique::BugPlusOne => Difficulty::Hard,
+            Technique::ColorTrap
+            | Technique::ColorWrap
+            | Technique::MultiColoring
+            | Technique::XChain
+            | Technique::XYChain => Difficulty::Expert,
+            Technique::AlternatingInferenceChain
+            | Technique::AlsXZ
+            | Technique::AlsXYWing
+            | Technique::AlsChain => Difficulty::Extreme,
+        }
+    }
+
+    /// The name the technique is counted under in the histogram,
+    /// where the sizes and types of a technique are added together.
+    fn histogram_name(&self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::Pointing => "Pointing",
+            Technique::BoxLine => "Box/Line Reduction",
+            Technique::CageCombination => "Cage Combination",
+            Technique::Innies | Technique::Outies => "Innies/Outies",
+            Technique::NakedSubset(_) | Technique::HiddenSubset(_) => "Subset",
+            Technique::Fish(_) => "Fish",
+            Technique::FinnedFish(_) | Technique::SashimiFish(_) => "Finned Fish",
+            Technique::Skyscraper => "Skyscraper",
+            Technique::TwoStringKite => "2-String Kite",
+            Technique::TurbotFish => "Turbot Fish",
+            Technique::EmptyRectangle => "Empty Rectangle",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::UniqueRectangle(_) | Technique::HiddenUniqueRectangle => "Unique Rectangle",
+            Technique::BugPlusOne => "BUG+1",
+            Technique::ColorTrap | Technique::ColorWrap => "Simple Coloring",
+            Technique::MultiColoring => "Multi-Coloring",
+            Technique::XChain => "X-Chain",
+            Technique::XYChain => "XY-Chain",
+            Technique::AlternatingInferenceChain => "AIC",
+            Technique::AlsXZ => "ALS-XZ",
+            Technique::AlsXYWing => "ALS-XY-Wing",
+            Technique::AlsChain => "ALS Chain",
+        }
+    }
+}
+
+impl SolveReport {
+    /// The difficulty of the hardest technique that was used.
+    ///
+    /// Puzzles the techniques couldn't finish, or that had
+    /// to be finished by brute force, are Extreme.
+    pub fn difficulty(&self) -> Difficulty {
+        if !self.is_solved || self.guessed {
+            return Difficulty::Extreme;
+        }
+        self.techniques
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(t, _)| t.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Beginner)
+    }
+
+    /// How much each technique was used, easiest first.
+    ///
+    /// Singles count the positions solved, everything else counts
+    /// the candidates removed. Techniques that weren't used are left out.
+    pub fn histogram(&self) -> Vec<(&'static str, usize)> {
+        let mut hist: Vec<(Difficulty, &'static str, usize)> = Vec::new();
+        for (t, count) in self.techniques.iter().filter(|(_, c)| **c > 0) {
+            let name = t.histogram_name();
+            match hist.iter_mut().find(|(_, n, _)| *n == name) {
+                Some((_, _, total)) => *total += count,
+                None => hist.push((t.difficulty(), name, *count)),
+            }
+        }
+        // Stable, so techniques keep their order within a difficulty.
+        hist.sort_by_key(|(d, _, _)| *d);
+        hist.into_iter()
+            .map(|(_, name, count)| (name, count))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::{SolveOptions, Solveable};
+
+    #[test]
+    fn test_difficulty_beginner() {
+        let sr = parse_sudoku(ONE_LINE).unwrap().try_solve();
+        assert_eq!(Difficulty::Beginner, sr.difficulty());
+        assert_eq!(
+            vec![("Hidden Single", 41), ("Naked Single", 16)],
+            sr.histogram()
+        );
+    }
+
+    #[test]
+    fn test_difficulty_buckets() {
+        let buckets: Vec<(&str, Difficulty)> = [
+            Technique::HiddenSingle,
+            Technique::NakedSingle,
+            Technique::Pointing,
+            Technique::BoxLine,
+            Technique::CageCombination,
+            Technique::Outies,
+            Technique::HiddenSubset(3),
+            Technique::SashimiFish(2),
+            Technique::HiddenUniqueRectangle,
+            Technique::ColorWrap,
+            Technique::AlsChain,
+        ]
+        .iter()
+        .map(|t| (t.histogram_name(), t.difficulty()))
+        .collect();
+        assert_eq!(
+            vec![
+                ("Hidden Single", Difficulty::Beginner),
+                ("Naked Single", Difficulty::Beginner),
+                ("Pointing", Difficulty::Easy),
+                ("Box/Line Reduction", Difficulty::Easy),
+                ("Cage Combination", Difficulty::Easy),
+                ("Innies/Outies", Difficulty::Medium),
+                ("Subset", Difficulty::Medium),
+                ("Finned Fish", Difficulty::Hard),
+                ("Unique Rectangle", Difficulty::Hard),
+                ("Simple Coloring", Difficulty::Expert),
+                ("ALS Chain", Difficulty::Extreme),
+            ],
+            buckets
+        );
+    }
+
+    #[test]
+    fn test_histogram_adds_up_sizes() {
+        let pzl = PUZZLES.lines().nth(13).unwrap();
+        let mut sr = parse_sudoku(pzl).unwrap().try_solve();
+        sr.techniques.insert(Technique::NakedSubset(2), 2);
+        sr.techniques.insert(Technique::HiddenSubset(3), 3);
+        sr.techniques.insert(Technique::CageCombination, 1);
+        assert_eq!(Difficulty::Medium, sr.difficulty());
+        assert_eq!(
+            vec![
+                ("Hidden Single", 46),
+                ("Naked Single", 13),
+                ("Pointing", 7),
+                ("Cage Combination", 1),
+                ("Subset", 5)
+            ],
+            sr.histogram()
+        );
+    }
+
+    #[test]
+    fn test_difficulty_easy() {
+        let pzl = PUZZLES.lines().nth(13).unwrap();
+        let sr = parse_sudoku(pzl).unwrap().try_solve();
+        assert_eq!(Difficulty::Easy, sr.difficulty());
+        assert_eq!(
+            vec![("Hidden Single", 46), ("Naked Single", 13), ("Pointing", 7)],
+            sr.histogram()
+        );
+    }
+
+    #[test]
+    fn test_difficulty_medium() {
+        let s = "...8...7..98.3....6.7...5........1.28....9..7.3.26.....7.3....49......1...26.19..";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert_eq!(Difficulty::Medium, sr.difficulty());
+        assert_eq!(
+            vec![
+                ("Hidden Single", 45),
+                ("Naked Single", 11),
+                ("Pointing", 7),
+                ("Box/Line Reduction", 3),
+                ("Subset", 2)
+            ],
+            sr.histogram()
+        );
+    }
+
+    #[test]
+    fn test_difficulty_already_solved() {
+        let s = "819637425527841369643529178476218953135796284298354716351962847764183592982475631";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert_eq!(Difficulty::Beginner, sr.difficulty());
+        assert!(sr.histogram().is_empty());
+    }
+
+    #[test]
+    fn test_difficulty_unsolved() {
+        let s = "9.....1.6..85.6.....4.9.....3.............7.98..7.2.5.71............34..4...68..2";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert_eq!(Difficulty::Extreme, sr.difficulty());
+        let options = SolveOptions {
+            brute_force: true,
+            ..Default::default()
+        };
+        let sr = parse_sudoku(s).unwrap().try_solve_with(options);
+        assert_eq!(Difficulty::Extreme, sr.difficulty());
+    }
+
+    #[test]
+    fn test_difficulty_lots() {
+        for x in PUZZLES.lines() {
+            let sr = parse_sudoku(x).unwrap().try_solve();
+            let d = sr.difficulty();
+            let hist = sr.histogram();
+            assert_eq!(sr.hidden_singles > 0, hist[0].0 == "Hidden Single");
+            if sr.aic > 0 || sr.als_xz > 0 {
+                assert_eq!(Difficulty::Extreme, d);
+            } else if sr.fish > 0 || sr.xy_wing > 0 {
+                assert!(d >= Difficulty::Hard);
+            }
+            if hist.iter().all(|(name, _)| name.ends_with("Single")) {
+                assert_eq!(Difficulty::Beginner, d);
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use crate::brute_force::search;
+use crate::candidate_set::{CandidateSet, CandidateSetIterator};
+use crate::index_helpers::get_index_tuple;
+use crate::sudoku::Sudoku;
+
+/// Solve as an exact cover problem using Knuth's Algorithm X with
+/// dancing links.
+///
+/// There are 324 constraints that each have to be covered exactly once:
+/// every position has a digit, and every row, column, and box has each
+/// digit once. Every candidate left in the puzzle is an option that
+/// covers one of each, so eliminated candidates are never tried.
+///
+/// Jigsaw boxes take the place of the 3x3 boxes and every extra region
+/// adds 9 more constraints, one for each digit. Killer cage sums and
+/// chess moves aren't an exact cover, so those puzzles fall back to the
+/// brute force search.
+pub trait DancingLinks {
+    /// The first solution found, or None if there isn't one.
+    fn dancing_links(&self) -> Option<Sudoku>;
+    /// Every solution up to `limit` of them.
+    fn solutions(&self, limit: usize) -> Vec<Sudoku>;
+}
+
+// The root header sits before the column headers.
+const ROOT: usize = 0;
+
+/// Can every rule of the puzzle be written as an exact cover.
+fn is_exact_cover(sudoku: &Sudoku) -> bool {
+    sudoku.cages().is_empty() && sudoku.chess().is_empty()
+}
+
+/// How many constraints there are with `regions` extra regions.
+fn num_columns(regions: usize) -> usize {
+    4 * 81 + regions * 9
+}
+
+/// The constraints that placing `m` at position `i` covers.
+fn constraints(sudoku: &Sudoku, i: usize, m: usize) -> Vec<usize> {
+    let (row_i, col_i, _) = get_index_tuple(i);
+    let d = m.trailing_zeros() as usize;
+    let mut c = vec![
+        i,
+        81 + row_i * 9 + d,
+        162 + col_i * 9 + d,
+        243 + sudoku.box_of(i) * 9 + d,
+    ];
+    for (n, region) in sudoku.regions().iter().enumerate() {
+        if region.cells.contains(&i) {
+            c.push(num_columns(n) + d);
+        }
+    }
+    c
+}
+
+/// A toroidal doubly linked list of every option.
+///
+/// Nodes are indexes into the vectors. The first is the root, then one
+/// header for each column, then a node for each constraint of each option.
+struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    // The column header of every node.
+    column: Vec<usize>,
+    // How many nodes are still in each column.
+    size: Vec<usize>,
+    // The option of every node, past the headers.
+    option: Vec<usize>,
+    // The position and candidate for each option.
+    options: Vec<(usize, usize)>,
+}
+
+impl Dlx {
+    fn new(sudoku: &Sudoku) -> Self {
+        let headers = num_columns(sudoku.regions().len()) + 1;
+        let mut dlx = Dlx {
+            left: (0..headers).map(|n| (n + headers - 1) % headers).collect(),
+            right: (0..headers).map(|n| (n + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            size: vec![0; headers],
+            option: vec![0; headers],
+            options: Vec::with_capacity(729),
+        };
+        for i in 0..81 {
+            for m in CandidateSetIterator::new(sudoku[i].get_candidates()) {
+                dlx.add_option(&constraints(sudoku, i, m), i, m);
+            }
+        }
+        dlx
+    }
+
+    fn add_option(&mut self, constraints: &[usize], i: usize, m: usize) {
+        let first = self.left.len();
+        let last = first + constraints.len() - 1;
+        for (k, c) in constraints.iter().enumerate() {
+            let n = first + k;
+            let c = c + 1;
+            self.left.push(if n == first { last } else { n - 1 });
+            self.right.push(if n == last { first } else { n + 1 });
+            // Add to the bottom of the column.
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            let above = self.up[c];
+            self.down[above] = n;
+            self.up[c] = n;
+            self.column.push(c);
+            self.size[c] += 1;
+            self.option.push(self.options.len());
+        }
+        self.options.push((i, m));
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    /// The uncovered column with the fewest options left.
+    fn choose_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut c = self.right[ROOT];
+        while c != ROOT {
+            if best.is_none_or(|b| self.size[c] < self.size[b]) {
+                best = Some(c);
+                if self.size[c] == 0 {
+                    break;
+                }
+            }
+            c = self.right[c];
+        }
+        best
+    }
+
+    fn search(
+        &mut self,
+        sudoku: &Sudoku,
+        chosen: &mut Vec<usize>,
+        limit: usize,
+        found: &mut Vec<Sudoku>,
+    ) {
+        let Some(c) = self.choose_column() else {
+            found.push(self.to_sudoku(sudoku, chosen));
+            return;
+        };
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c && found.len() < limit {
+            chosen.push(self.option[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+            self.search(sudoku, chosen, limit, found);
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            chosen.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+    }
+
+    fn to_sudoku(&self, sudoku: &Sudoku, chosen: &[usize]) -> Sudoku {
+        let mut s = sudoku.clone();
+        for o in chosen {
+            let (i, m) = self.options[*o];
+            s[i] = CandidateSet::new(m);
+            s[i].set_solved();
+        }
+        s
+    }
+}
+
+impl DancingLinks for Sudoku {
+    fn dancing_links(&self) -> Option<Sudoku> {
+        self.solutions(1).pop()
+    }
+
+    fn solutions(&self, limit: usize) -> Vec<Sudoku> {
+        let mut found = Vec::new();
+        if limit == 0 {
+            return found;
+        }
+        if is_exact_cover(self) {
+            let mut chosen = Vec::with_capacity(81);
+            Dlx::new(self).search(self, &mut chosen, limit, &mut found);
+        } else {
+            search(self, &mut |s| {
+                found.push(s.clone());
+                found.len() < limit
+            });
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::BruteForce;
+    use crate::chess::ChessMove;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::region::Region;
+
+    #[test]
+    fn test_dancing_links_easy() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let solution = p.dancing_links().unwrap();
+        assert!(solution.is_solved());
+        assert!(solution.is_valid());
+        assert_eq!(
+            "819637425527841369643529178476218953135796284298354716351962847764183592982475631",
+            solution.oneline()
+        );
+    }
+
+    #[test]
+    fn test_dancing_links_lots() {
+        for x in PUZZLES.lines().chain([HARD]) {
+            let p = parse_sudoku(x).unwrap();
+            let solution = p.dancing_links().unwrap();
+            assert!(solution.is_valid());
+            assert_eq!(p.brute_force().unwrap().oneline(), solution.oneline());
+        }
+    }
+
+    #[test]
+    fn test_dancing_links_no_solution() {
+        // Valid looking, but r1c8 and r1c9 can only be 9.
+        let s = "123456............................7.........8................8.........7.........";
+        let p = parse_sudoku(s).unwrap();
+        assert!(p.dancing_links().is_none());
+        assert!(p.solutions(10).is_empty());
+    }
+
+    #[test]
+    fn test_dancing_links_all_solutions() {
+        // A solved grid missing a rectangle where the digits can swap.
+        let s = "8196.7.255278.1.69643529178476218953135796284298354716351962847764183592982475631";
+        let p = parse_sudoku(s).unwrap();
+        let mut found: Vec<_> = p.solutions(10).iter().map(|s| s.oneline()).collect();
+        found.sort();
+        assert_eq!(2, found.len());
+        assert_ne!(found[0], found[1]);
+        assert_eq!(1, p.solutions(1).len());
+        assert!(p.solutions(0).is_empty());
+    }
+
+    #[test]
+    fn test_dancing_links_uses_candidates() {
+        let s = "8196.7.255278.1.69643529178476218953135796284298354716351962847764183592982475631";
+        let mut p = parse_sudoku(s).unwrap();
+        // Only keep 3 in r1c5 and that picks one of the two solutions.
+        p[4] = CandidateSet::new(1 << 2);
+        let found = p.solutions(10);
+        assert_eq!(1, found.len());
+        assert_eq!(Some(3), found[0][4].value());
+        // Taking away every candidate leaves nothing to find.
+        p[4] = CandidateSet::new(0);
+        assert!(p.dancing_links().is_none());
+    }
+
+    #[test]
+    fn test_dancing_links_variants() {
+        let x = parse_sudoku_with_regions(X_SUDOKU, Region::x_sudoku()).unwrap();
+        let windoku = parse_sudoku_with_regions(WINDOKU, Region::windoku()).unwrap();
+        let jigsaw = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        for p in [x, windoku, jigsaw] {
+            let found = p.solutions(2);
+            assert_eq!(1, found.len());
+            assert!(found[0].is_solved() && found[0].is_valid());
+            assert_eq!(p.brute_force().unwrap().oneline(), found[0].oneline());
+        }
+    }
+
+    #[test]
+    fn test_dancing_links_not_exact_cover() {
+        // Cage sums and chess moves can't be covered so brute force is used.
+        let killer = parse_killer(&".".repeat(81), KILLER).unwrap();
+        let knight = parse_sudoku_with_chess(ANTI_KNIGHT, vec![ChessMove::Knight]).unwrap();
+        for p in [killer, knight] {
+            let found = p.solutions(2);
+            assert_eq!(1, found.len());
+            assert!(found[0].is_solved() && found[0].is_valid());
+            assert_eq!(p.brute_force().unwrap().oneline(), found[0].oneline());
+            assert_eq!(found[0].oneline(), p.dancing_links().unwrap().oneline());
+        }
+    }
+}
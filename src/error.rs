@@ -3,4 +3,15 @@ pub enum SudokuErr {
     Ascii(),
     Parse(),
     InvalidPuzzle(),
+    /// The puzzle looks valid but can't be solved.
+    NoSolution(),
+    /// The puzzle has more than one solution.
+    MultipleSolutions(),
+    /// A Killer cage is empty, too big, overlaps another cage,
+    /// or has a sum that different digits can't make.
+    InvalidCage(),
+    /// An extra region has a position twice or one that's off the grid.
+    InvalidRegion(),
+    /// A Jigsaw box doesn't have nine positions joined up through their sides.
+    InvalidJigsaw(),
 }
@@ -37,3 +37,71 @@ pub const PUZZLES: &str =
 
 pub const HARD: &str =
     "....5..6.9.5..82...4..97.5..64......5.2.8.7.1......63..8.52..1...39..4.2.2..6....";
+
+/// Killer Sudoku cages for a puzzle with no givens.
+pub const KILLER: &str = "
+    19: r6c7 r7c7 r7c8
+    10: r4c2 r5c2
+    20: r3c8 r3c9 r4c8
+    14: r6c1 r6c2 r7c1
+    14: r5c5 r6c5
+    19: r4c6 r4c7 r5c7
+    20: r1c5 r1c6 r2c6 r3c6
+    9: r8c4 r8c5
+    15: r2c3 r2c4
+    13: r3c2 r3c3 r4c3
+    21: r7c9 r8c8 r8c9 r9c8
+    16: r8c2 r9c2 r9c3
+    14: r1c1 r1c2 r2c1
+    4: r2c7 r3c7
+    18: r6c3 r7c2 r7c3 r8c3
+    15: r3c4 r4c4 r4c5 r5c4
+    11: r3c1 r4c1 r5c1
+    16: r7c6 r8c6 r8c7 r9c7
+    14: r1c9 r2c9
+    6: r2c5 r3c5
+    16: r9c4 r9c5 r9c6
+    18: r6c4 r7c4 r7c5
+    15: r1c3 r1c4
+    15: r5c8 r6c8 r6c9
+    2: r2c2
+    16: r8c1 r9c1
+    8: r1c8 r2c8
+    10: r5c6 r6c6
+    4: r1c7
+    1: r9c9
+    7: r4c9 r5c9
+    5: r5c3";
+
+/// An X-Sudoku, only unique with both diagonals.
+pub const X_SUDOKU: &str =
+    "4..7.5...7..8.3654.2....1.7........2....69.3191.....6..7..3..9.18..5......9...4.3";
+
+/// A Windoku, only unique with the four extra windows.
+pub const WINDOKU: &str =
+    "4..7.5...7..8.3564.9....1.8........5....59.1695.....3..8..3..9.67..8......9...4.3";
+
+/// The boxes of `JIGSAW`, numbered for every position.
+pub const JIGSAW_LAYOUT: &str = "111222333
+                                 112252333
+                                 111252633
+                                 414452636
+                                 444555666
+                                 444585696
+                                 777785699
+                                 777888899
+                                 778889999";
+
+/// A Jigsaw Sudoku with the irregular boxes of `JIGSAW_LAYOUT`.
+pub const JIGSAW: &str =
+    "4695..1......3.9..3....72..7.....5.9......6...4..5........6.8....5.7.4.663..94.2.";
+
+/// An Anti-Knight Sudoku, only unique when the same digit can't be
+/// a knight's move apart.
+pub const ANTI_KNIGHT: &str =
+    "....5........1.4.6...7.....251.8....7......9........3.........7....6.....4.......";
+
+/// An Anti-King Sudoku, only unique when the same digit can't be
+/// a king's move apart.
+pub const ANTI_KING: &str =
+    "....56.......9..46...1.....8.253..9.3..........67...24........3.....1....5.......";
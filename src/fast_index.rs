@@ -4,7 +4,7 @@ use std::iter::Iterator;
 #[derive(Debug)]
 pub struct FastIndexIter {
     incr: usize,
-    idx_pos: &'static [u8],
+    idx_pos: &'static [u16],
 }
 impl Iterator for FastIndexIter {
     type Item = usize;
@@ -19,47 +19,68 @@ impl Iterator for FastIndexIter {
     }
 }
 
-pub fn row_iter(idx: usize) -> FastIndexIter {
-    let start = idx * 9;
-    let end = start + 9;
-    FastIndexIter {
-        incr: 0,
-        idx_pos: &ROW_DATA[start..end],
+/// The row, column, and box tables for a grid with `digits` digits.
+fn sized_data(digits: usize) -> [&'static [u16]; 3] {
+    match digits {
+        4 => [&ROW_DATA_4, &COLUMN_DATA_4, &BOX_DATA_4],
+        6 => [&ROW_DATA_6, &COLUMN_DATA_6, &BOX_DATA_6],
+        9 => [&ROW_DATA, &COLUMN_DATA, &BOX_DATA],
+        16 => [&ROW_DATA_16, &COLUMN_DATA_16, &BOX_DATA_16],
+        25 => [&ROW_DATA_25, &COLUMN_DATA_25, &BOX_DATA_25],
+        _ => panic!("There are no index tables for {} digits", digits),
     }
 }
-pub fn column_iter(idx: usize) -> FastIndexIter {
-    let start = idx * 9;
-    let end = start + 9;
+
+fn sized_iter(table: usize, digits: usize, idx: usize) -> FastIndexIter {
+    let start = idx * digits;
+    let end = start + digits;
     FastIndexIter {
         incr: 0,
-        idx_pos: &COLUMN_DATA[start..end],
+        idx_pos: &sized_data(digits)[table][start..end],
     }
 }
+
+pub fn row_iter(idx: usize) -> FastIndexIter {
+    sized_row_iter(9, idx)
+}
+pub fn column_iter(idx: usize) -> FastIndexIter {
+    sized_column_iter(9, idx)
+}
 pub fn box_iter(idx: usize) -> FastIndexIter {
-    let start = idx * 9;
-    let end = start + 9;
-    FastIndexIter {
-        incr: 0,
-        idx_pos: &BOX_DATA[start..end],
-    }
+    sized_box_iter(9, idx)
+}
+
+/// Row `idx` of a grid with `digits` digits, see `BoxSize`.
+pub fn sized_row_iter(digits: usize, idx: usize) -> FastIndexIter {
+    sized_iter(0, digits, idx)
+}
+/// Column `idx` of a grid with `digits` digits.
+pub fn sized_column_iter(digits: usize, idx: usize) -> FastIndexIter {
+    sized_iter(1, digits, idx)
+}
+/// Box `idx` of a grid with `digits` digits. The boxes are numbered
+/// left to right then top to bottom.
+pub fn sized_box_iter(digits: usize, idx: usize) -> FastIndexIter {
+    sized_iter(2, digits, idx)
 }
 
 #[derive(Debug)]
 pub struct CombinationIterator {
     num_idx: usize,
-    idx: [usize; 4],
-    values: &'static [u8],
+    idx: [usize; 8],
+    values: &'static [u16],
 }
 
 impl CombinationIterator {
-    pub fn new(num_idx: usize, values: &'static [u8]) -> Self {
-        assert!(num_idx <= 4);
-        assert!(num_idx >= 2);
-        let mut idx = [0; 4];
+    pub fn new(num_idx: usize, values: &'static [u16]) -> Self {
+        assert!(num_idx <= 8);
+        assert!(num_idx >= 1);
+        let mut idx = [0; 8];
         for (i, idx_item) in idx.iter_mut().enumerate().take(num_idx) {
             *idx_item = i;
         }
-        idx[num_idx - 1] -= 1;
+        // This wraps for a single index; next() wraps it back to 0.
+        idx[num_idx - 1] = idx[num_idx - 1].wrapping_sub(1);
         CombinationIterator {
             num_idx,
             idx,
@@ -76,9 +97,9 @@ impl Iterator for CombinationIterator {
 
         // While there are still levels to visit
         // keep trying to increment.
-        while current_level < 4 {
+        while current_level < self.num_idx {
             // Move the current level forward one unconditionally
-            self.idx[current_level] += 1;
+            self.idx[current_level] = self.idx[current_level].wrapping_add(1);
 
             // Calculate how many positions/numbers are needed
             // to fill out the remaining levels.
@@ -129,9 +150,17 @@ pub fn box_comb_iter(idx: usize, sz: usize) -> CombinationIterator {
     CombinationIterator::new(sz, &BOX_DATA[start..end])
 }
 
+/// Combinations of `sz` row, column, or box numbers.
+pub fn group_comb_iter(sz: usize) -> CombinationIterator {
+    // The first row is made up of the positions 0 through 8
+    // which are also all of the possible group numbers.
+    CombinationIterator::new(sz, &ROW_DATA[0..9])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sized::BoxSize;
     use crate::slow_index;
     #[test]
     fn test_iter() {
@@ -156,7 +185,7 @@ mod tests {
     }
     #[test]
     fn test_comb_iter() {
-        for sz in 2..5 {
+        for sz in 1..9 {
             for row in 0..9 {
                 let i = row_comb_iter(row, sz).zip(slow_index::row_comb_iter(row, sz));
                 for (fast, slow) in i {
@@ -177,4 +206,30 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_sized_iter() {
+        for size in [
+            BoxSize::FOUR,
+            BoxSize::SIX,
+            BoxSize::SIXTEEN,
+            BoxSize::TWENTY_FIVE,
+        ] {
+            let n = size.digits();
+            for idx in 0..n {
+                assert_eq!(size.row(idx), sized_row_iter(n, idx).collect::<Vec<_>>());
+                assert_eq!(
+                    size.column(idx),
+                    sized_column_iter(n, idx).collect::<Vec<_>>()
+                );
+                assert_eq!(size.boxed(idx), sized_box_iter(n, idx).collect::<Vec<_>>());
+            }
+        }
+    }
+    #[test]
+    fn test_group_comb_iter() {
+        // 9 choose 2 = 36
+        assert_eq!(36, group_comb_iter(2).count());
+        assert_eq!(Some(vec![0, 1, 2]), group_comb_iter(3).next());
+        assert_eq!(Some(vec![5, 6, 7, 8]), group_comb_iter(4).last());
+    }
 }
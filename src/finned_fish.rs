@@ -0,0 +1,214 @@
+use crate::fast_index::group_comb_iter;
+use crate::fish::{base_cells, cover_grouping, line_positions};
+use crate::index::Grouping;
+use crate::index_helpers::*;
+use crate::step::{Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+
+pub trait FinnedFish {
+    fn find_finned_fish(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// Get the position index for the `pos` position of line `line`.
+fn cell(g: Grouping, line: usize, pos: usize) -> usize {
+    match g {
+        Grouping::Row => to_index(line, pos),
+        _ => to_index(pos, line),
+    }
+}
+
+fn finned_for(sudoku: &Sudoku, size: usize, g: Grouping, val_idx: usize) -> Vec<Step> {
+    let m = 1 << val_idx;
+    let positions = line_positions(sudoku, g, m);
+    group_comb_iter(size)
+        // Every base line has to have the digit in it.
+        .filter(|base| base.iter().all(|b| positions[*b] != 0))
+        .flat_map(|base| {
+            let all = base.iter().fold(0, |a, b| a | positions[*b]);
+            // Without anything extra this is a plain fish.
+            if all.count_ones() as usize <= size {
+                return vec![];
+            }
+            group_comb_iter(size)
+                .map(|c| c.iter().fold(0, |a, p| a | (1 << p)))
+                .filter(|cover| cover & !all == 0)
+                .filter_map(|cover| finned_step(sudoku, g, &base, &positions, cover, val_idx))
+                .collect()
+        })
+        .collect()
+}
+
+fn finned_step(
+    sudoku: &Sudoku,
+    g: Grouping,
+    base: &[usize],
+    positions: &[usize; 9],
+    cover: usize,
+    val_idx: usize,
+) -> Option<Step> {
+    let m = 1 << val_idx;
+    // Each base line needs to still have part of the fish in it.
+    if base.iter().any(|b| positions[*b] & cover == 0) {
+        return None;
+    }
+    // Everything outside of the cover lines is a fin.
+    let fins: Vec<usize> = base
+        .iter()
+        .flat_map(|b| {
+            (0..9)
+                .filter(move |p| positions[*b] & !cover & (1 << p) != 0)
+                .map(move |p| cell(g, *b, p))
+        })
+        .collect();
+    // All the fins have to be in the same box.
+    let fin_box = sudoku.box_of(fins[0]);
+    if fins.iter().any(|f| sudoku.box_of(*f) != fin_box) {
+        return None;
+    }
+    // Either one of the fins is the digit or the fish is, so
+    // anything in the cover lines that can see all the fins is removed.
+    let eliminations: Vec<Candidate> = (0..9)
+        .filter(|c| cover & (1 << c) != 0)
+        .flat_map(|c| {
+            (0..9)
+                .filter(|line| !base.contains(line))
+                .map(move |line| cell(g, line, c))
+        })
+        .filter(|i| sudoku.box_of(*i) == fin_box && !sudoku[*i].is_solved())
+        .flat_map(|i| Candidate::from_mask(i, sudoku[i].get() & m))
+        .collect();
+    if eliminations.is_empty() {
+        return None;
+    }
+    let size = base.len();
+    // If taking away the fins leaves a line with only one position
+    // then the fish would be degenerate.
+    let technique = if base
+        .iter()
+        .any(|b| (positions[*b] & cover).count_ones() == 1)
+    {
+        Technique::SashimiFish(size)
+    } else {
+        Technique::FinnedFish(size)
+    };
+    let cover_units = (0..9)
+        .filter(|c| cover & (1 << c) != 0)
+        .map(|c| Unit::new(cover_grouping(g), c));
+    Some(Step {
+        technique,
+        units: base
+            .iter()
+            .map(|b| Unit::new(g, *b))
+            .chain(cover_units)
+            .chain(std::iter::once(Unit::new(Grouping::Box, fin_box)))
+            .collect(),
+        cells: base_cells(sudoku, g, base, m),
+        digits: vec![val_idx + 1],
+        eliminations,
+        placements: vec![],
+        links: vec![],
+        sets: vec![],
+    })
+}
+
+impl FinnedFish for Sudoku {
+    fn find_finned_fish(&self) -> impl Iterator<Item = Step> + '_ {
+        (2..5).flat_map(move |size| {
+            [Grouping::Row, Grouping::Column]
+                .into_iter()
+                .flat_map(move |g| {
+                    (0..9).flat_map(move |val_idx| finned_for(self, size, g, val_idx))
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::HARD;
+    use crate::parse::*;
+    use crate::solve::{next_step, Solveable};
+
+    /// Step `sudoku` forward until `find_finned_fish` has a step of
+    /// `technique` that makes `eliminations`. The single digit patterns
+    /// come first in the solve order and make the same eliminations as
+    /// smaller finned fish, so the solver itself might never use it.
+    fn find_finned(sudoku: &mut Sudoku, technique: Technique, eliminations: &[Candidate]) -> Step {
+        loop {
+            if let Some(step) = sudoku
+                .find_finned_fish()
+                .find(|s| s.technique == technique && s.eliminations == eliminations)
+            {
+                return step;
+            }
+            next_step(sudoku).unwrap().apply(sudoku);
+        }
+    }
+
+    #[test]
+    fn test_finned_x_wing() {
+        let s = ".....5....2...4.1..3..8..2......84..8..6......9..1.7.5..6......95...3.6...3.....1";
+        let mut p = parse_sudoku(s).unwrap();
+        let sr = p.clone().try_solve();
+        assert!(sr.is_valid);
+        let eliminations = [Candidate::new(to_index(2, 2), 1)];
+        let step = find_finned(&mut p, Technique::FinnedFish(2), &eliminations);
+        assert_eq!(vec![1], step.digits);
+        // The fin is in box 1
+        assert_eq!(Some(&Unit::new(Grouping::Box, 0)), step.units.last());
+    }
+
+    #[test]
+    fn test_finned_swordfish() {
+        let s = "..4.2..3....8.9.........7...5..37..8........5.49.6..1.5.........68........7.4.9.1";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.is_valid);
+        assert_eq!(1, sr.finned_fish);
+    }
+
+    #[test]
+    fn test_sashimi_x_wing() {
+        let s = ".94...13..............76..2.8..1.....32.........2...6.....5.4.......8..7..63.4..8";
+        let mut p = parse_sudoku(s).unwrap();
+        let eliminations = [Candidate::new(to_index(3, 2), 5)];
+        let step = find_finned(&mut p, Technique::SashimiFish(2), &eliminations);
+        assert_eq!(vec![5], step.digits);
+        // The fins are all in box 4
+        assert_eq!(Some(&Unit::new(Grouping::Box, 3)), step.units.last());
+    }
+
+    #[test]
+    fn test_skyscraper_is_sashimi() {
+        let s = ".94...13..............76..2.8..1.....32.........2...6.....5.4.......8..7..63.4..8";
+        let mut p = parse_sudoku(s).unwrap();
+        // Step forward until a skyscraper is needed.
+        // Every skyscraper is also a sashimi X-Wing.
+        let skyscraper = loop {
+            let step = next_step(&p).unwrap();
+            if step.technique == Technique::Skyscraper {
+                break step;
+            }
+            step.apply(&mut p);
+        };
+        assert!(p.find_finned_fish().any(|s| {
+            s.technique == Technique::SashimiFish(2)
+                && s.digits == skyscraper.digits
+                && s.eliminations == skyscraper.eliminations
+        }));
+    }
+
+    #[test]
+    fn test_hard() {
+        let mut p = parse_sudoku(HARD).unwrap();
+        let sr = p.clone().try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.is_valid);
+        let eliminations = [Candidate::new(to_index(1, 7), 4)];
+        let step = find_finned(&mut p, Technique::SashimiFish(2), &eliminations);
+        assert_eq!(vec![4], step.digits);
+        // The fins are all in box 3
+        assert_eq!(Some(&Unit::new(Grouping::Box, 2)), step.units.last());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::fast_index::group_comb_iter;
+use crate::index::Grouping;
+use crate::step::{Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+
+pub trait Fish {
+    fn find_fish(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// The grouping that crosses `g`. Rows are covered by columns
+/// and columns by rows.
+pub(crate) fn cover_grouping(g: Grouping) -> Grouping {
+    match g {
+        Grouping::Row => Grouping::Column,
+        _ => Grouping::Row,
+    }
+}
+
+/// For every row or column of `g` get a mask of the positions
+/// in that line where `m` is still an unsolved candidate.
+///
+/// Lines where the digit is already solved are 0, as they
+/// can't be part of any fish.
+pub(crate) fn line_positions(sudoku: &Sudoku, g: Grouping, m: usize) -> [usize; 9] {
+    let mut positions = [0; 9];
+    for (line, p) in positions.iter_mut().enumerate() {
+        for (pos, i) in g.iter(line).enumerate() {
+            if sudoku[i].is_solved() {
+                if sudoku[i].get_candidates() == m {
+                    *p = 0;
+                    break;
+                }
+            } else if sudoku[i].get() & m != 0 {
+                *p |= 1 << pos;
+            }
+        }
+    }
+    positions
+}
+
+/// Remove `m` from every unsolved position of the `cover` lines
+/// that isn't in one of the `base` lines.
+pub(crate) fn cover_eliminations(
+    sudoku: &Sudoku,
+    g: Grouping,
+    base: &[usize],
+    cover: usize,
+    m: usize,
+) -> Vec<Candidate> {
+    (0..9)
+        .filter(|c| cover & (1 << c) != 0)
+        .flat_map(|c| {
+            cover_grouping(g)
+                .iter(c)
+                .enumerate()
+                .filter(|(line, _)| !base.contains(line))
+                .map(|(_, i)| i)
+        })
+        .filter(|i| !sudoku[*i].is_solved())
+        .flat_map(|i| Candidate::from_mask(i, sudoku[i].get() & m))
+        .collect()
+}
+
+/// The cells in the base lines that hold the digit.
+pub(crate) fn base_cells(sudoku: &Sudoku, g: Grouping, base: &[usize], m: usize) -> Vec<usize> {
+    base.iter()
+        .flat_map(|b| g.iter(*b))
+        .filter(|i| !sudoku[*i].is_solved() && sudoku[*i].get() & m != 0)
+        .collect()
+}
+
+fn fish_for(sudoku: &Sudoku, size: usize, g: Grouping, val_idx: usize) -> Vec<Step> {
+    let m = 1 << val_idx;
+    let positions = line_positions(sudoku, g, m);
+    group_comb_iter(size)
+        // Every base line has to have the digit in it.
+        .filter(|base| base.iter().all(|b| positions[*b] != 0))
+        .filter_map(|base| {
+            let cover = base.iter().fold(0, |a, b| a | positions[*b]);
+            // If the digit in `size` lines is confined to `size` cover lines
+            // then each cover line has to have the digit in one of the base lines.
+            if cover.count_ones() as usize != size {
+                return None;
+            }
+            let eliminations = cover_eliminations(sudoku, g, &base, cover, m);
+            if eliminations.is_empty() {
+                return None;
+            }
+            let cover_units = (0..9)
+                .filter(|c| cover & (1 << c) != 0)
+                .map(|c| Unit::new(cover_grouping(g), c));
+            Some(Step {
+                technique: Technique::Fish(size),
+                units: base
+                    .iter()
+                    .map(|b| Unit::new(g, *b))
+                    .chain(cover_units)
+                    .collect(),
+                cells: base_cells(sudoku, g, &base, m),
+                digits: vec![val_idx + 1],
+                eliminations,
+                placements: vec![],
+                links: vec![],
+                sets: vec![],
+            })
+        })
+        .collect()
+}
+
+impl Fish for Sudoku {
+    fn find_fish(&self) -> impl Iterator<Item = Step> + '_ {
+        // X-Wing, Swordfish, then Jellyfish
+        (2..5).flat_map(move |size| {
+            [Grouping::Row, Grouping::Column]
+                .into_iter()
+                .flat_map(move |g| (0..9).flat_map(move |val_idx| fish_for(self, size, g, val_idx)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+
+    #[test]
+    fn test_x_wing() {
+        // 7 in rows 2 and 6 is confined to columns 4 and 8.
+        let s = "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5";
+        let p = parse_sudoku(s).unwrap();
+        let step = p.find_fish().next().unwrap();
+        assert_eq!(Technique::Fish(2), step.technique);
+        assert_eq!(vec![7], step.digits);
+        assert_eq!(
+            vec![
+                Unit::new(Grouping::Row, 1),
+                Unit::new(Grouping::Row, 5),
+                Unit::new(Grouping::Column, 3),
+                Unit::new(Grouping::Column, 7),
+            ],
+            step.units
+        );
+        assert_eq!(4, step.cells.len());
+        assert!(step.eliminations.iter().all(|c| c.digit == 7));
+        assert!(p.try_solve().fish > 0);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::difficulty::Difficulty;
+use crate::index_helpers::{classic_sees, get_index_tuple, to_index};
+use crate::parse::parse_sudoku;
+use crate::solve::Solveable;
+use crate::sudoku::Sudoku;
+
+/// A small seedable random number generator, SplitMix64.
+///
+/// http://prng.di.unimi.it/splitmix64.c
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in 0..n.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, v: &mut [T]) {
+        for i in (1..v.len()).rev() {
+            v.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Which clues have to be kept or removed together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// Every clue is on its own.
+    #[default]
+    None,
+    /// The same after turning the grid half way around.
+    Rotational180,
+    /// The same after turning the grid a quarter of the way around.
+    Rotational90,
+    /// Mirrored across the diagonal from r1c1 to r9c9.
+    Diagonal,
+    /// Mirrored across the diagonal from r1c9 to r9c1.
+    AntiDiagonal,
+    /// Mirrored top to bottom across the middle row.
+    Horizontal,
+    /// Mirrored left to right across the middle column.
+    Vertical,
+}
+
+impl Symmetry {
+    /// All the positions that `i` maps to, including `i`, in order.
+    pub fn orbit(self, i: usize) -> Vec<usize> {
+        let (r, c, _) = get_index_tuple(i);
+        let mut orbit = match self {
+            Symmetry::None => vec![i],
+            Symmetry::Rotational180 => vec![i, to_index(8 - r, 8 - c)],
+            Symmetry::Rotational90 => vec![
+                i,
+                to_index(c, 8 - r),
+                to_index(8 - r, 8 - c),
+                to_index(8 - c, r),
+            ],
+            Symmetry::Diagonal => vec![i, to_index(c, r)],
+            Symmetry::AntiDiagonal => vec![i, to_index(8 - c, 8 - r)],
+            Symmetry::Horizontal => vec![i, to_index(8 - r, c)],
+            Symmetry::Vertical => vec![i, to_index(r, 8 - c)],
+        };
+        orbit.sort_unstable();
+        orbit.dedup();
+        orbit
+    }
+}
+
+/// Options that change the puzzles a `Generator` makes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenerateOptions {
+    /// The pattern the clues have to keep.
+    pub symmetry: Symmetry,
+}
+
+/// Creates new puzzles with a single solution.
+///
+/// The same seed always gives the same puzzles.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+}
+
+/// Turn digits, with 0 for empty, into a puzzle.
+fn to_sudoku(digits: &[usize; 81]) -> Sudoku {
+    let s: String = digits
+        .iter()
+        .map(|d| match d {
+            0 => '.',
+            _ => char::from_digit(*d as u32, 10).unwrap(),
+        })
+        .collect();
+    parse_sudoku(&s).unwrap()
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator { rng: Rng(seed) }
+    }
+
+    /// Fill in the positions from `i` on, trying digits in a random order.
+    fn fill(&mut self, digits: &mut [usize; 81], i: usize) -> bool {
+        if i == 81 {
+            return true;
+        }
+        let mut order = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        self.rng.shuffle(&mut order);
+        for d in order {
+            if (0..i).any(|p| digits[p] == d && classic_sees(i, p)) {
+                continue;
+            }
+            digits[i] = d;
+            if self.fill(digits, i + 1) {
+                return true;
+            }
+        }
+        digits[i] = 0;
+        false
+    }
+
+    fn full_digits(&mut self) -> [usize; 81] {
+        let mut digits = [0; 81];
+        self.fill(&mut digits, 0);
+        digits
+    }
+
+    /// A random solved grid.
+    pub fn full_grid(&mut self) -> Sudoku {
+        to_sudoku(&self.full_digits())
+    }
+
+    /// A random puzzle with a single solution where `try_solve`
+    /// rates it as `difficulty`.
+    ///
+    /// Clues are removed from a full grid in a random order. Any removal
+    /// that allows a second solution or makes the puzzle harder than
+    /// `difficulty` is put back. If the result is too easy then it
+    /// starts over with a new grid.
+    pub fn generate(&mut self, difficulty: Difficulty) -> Sudoku {
+        self.generate_with(difficulty, GenerateOptions::default())
+    }
+
+    /// Like `generate` but the clues are removed a whole
+    /// symmetry orbit at a time.
+    pub fn generate_with(&mut self, difficulty: Difficulty, options: GenerateOptions) -> Sudoku {
+        loop {
+            let mut digits = self.full_digits();
+            // Each orbit once, starting from its smallest position.
+            let mut orbits: Vec<Vec<usize>> = (0..81)
+                .map(|i| options.symmetry.orbit(i))
+                .enumerate()
+                .filter(|(i, orbit)| orbit[0] == *i)
+                .map(|(_, orbit)| orbit)
+                .collect();
+            self.rng.shuffle(&mut orbits);
+            let mut current = None;
+            for orbit in orbits {
+                let kept = digits;
+                orbit.iter().for_each(|i| digits[*i] = 0);
+                let p = to_sudoku(&digits);
+                if !p.has_unique_solution() {
+                    digits = kept;
+                    continue;
+                }
+                match p.try_solve().difficulty() {
+                    harder if harder > difficulty => digits = kept,
+                    ok => current = Some(ok),
+                }
+            }
+            if current == Some(difficulty) {
+                return to_sudoku(&digits);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_grid() {
+        let mut g = Generator::new(7);
+        let a = g.full_grid();
+        let b = g.full_grid();
+        assert!(a.is_solved() && a.is_valid());
+        assert!(b.is_solved() && b.is_valid());
+        assert_ne!(a.oneline(), b.oneline());
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let a = Generator::new(42).generate(Difficulty::Easy);
+        let b = Generator::new(42).generate(Difficulty::Easy);
+        assert_eq!(a.oneline(), b.oneline());
+        let c = Generator::new(43).generate(Difficulty::Easy);
+        assert_ne!(a.oneline(), c.oneline());
+    }
+
+    #[test]
+    fn test_orbits() {
+        // r1c2
+        assert_eq!(vec![1], Symmetry::None.orbit(1));
+        assert_eq!(vec![1, 79], Symmetry::Rotational180.orbit(1));
+        assert_eq!(vec![1, 17, 63, 79], Symmetry::Rotational90.orbit(1));
+        assert_eq!(vec![1, 9], Symmetry::Diagonal.orbit(1));
+        assert_eq!(vec![1, 71], Symmetry::AntiDiagonal.orbit(1));
+        assert_eq!(vec![1, 73], Symmetry::Horizontal.orbit(1));
+        assert_eq!(vec![1, 7], Symmetry::Vertical.orbit(1));
+        // The center maps to itself.
+        assert_eq!(vec![40], Symmetry::Rotational90.orbit(40));
+        // Every position is in exactly one orbit.
+        for i in 0..81 {
+            for j in Symmetry::Rotational90.orbit(i) {
+                assert_eq!(
+                    Symmetry::Rotational90.orbit(i),
+                    Symmetry::Rotational90.orbit(j)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_symmetric() {
+        let mut g = Generator::new(3);
+        for symmetry in [
+            Symmetry::Rotational180,
+            Symmetry::Rotational90,
+            Symmetry::Diagonal,
+            Symmetry::AntiDiagonal,
+            Symmetry::Horizontal,
+            Symmetry::Vertical,
+        ] {
+            let options = GenerateOptions { symmetry };
+            let p = g.generate_with(Difficulty::Easy, options);
+            assert!(p.has_unique_solution());
+            assert_eq!(Difficulty::Easy, p.clone().try_solve().difficulty());
+            for i in 0..81 {
+                let given = p[i].is_solved();
+                assert!(symmetry.orbit(i).iter().all(|j| p[*j].is_solved() == given));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_difficulty() {
+        let mut g = Generator::new(1);
+        for difficulty in [Difficulty::Beginner, Difficulty::Easy, Difficulty::Medium] {
+            let p = g.generate(difficulty);
+            assert!(p.has_unique_solution());
+            assert!(!p.is_solved());
+            assert_eq!(difficulty, p.try_solve().difficulty());
+        }
+    }
+}
@@ -1,41 +1,54 @@
-use crate::candidate_set::CandidateSet;
-use crate::index::ALL_GROUPINGS;
+use crate::index::Grouping;
+use crate::step::{Candidate, Step, Technique, Unit};
 use crate::sudoku::Sudoku;
 use std::iter::Iterator;
 
 pub trait HiddenSingles {
-    fn find_hidden(&mut self) -> usize;
+    /// The hidden singles in one row, column, box, or extra region.
+    fn find_hidden_in(&self, g: Grouping, g_idx: usize) -> Vec<Step>;
+    /// The hidden singles in every house.
+    fn find_hidden(&self) -> impl Iterator<Item = Step> + '_;
 }
 
-impl HiddenSingles for Sudoku {
-    fn find_hidden(&mut self) -> usize {
-        let mut solved = 0;
-        // For all the grouping types
-        for g in ALL_GROUPINGS.iter() {
-            // For row,col,box number 0 through 8
-            for g_idx in 0..9 {
-                let mut counts = [0; 9];
-                let mut last_index = [0; 9];
-                for i in g.iter(g_idx) {
-                    for c in self[i] {
-                        let cc = c.trailing_zeros() as usize;
-                        counts[cc] += 1;
-                        last_index[cc] = i;
-                    }
-                }
+fn hidden_in_unit(sudoku: &Sudoku, g: Grouping, g_idx: usize) -> Vec<Step> {
+    let mut counts = [0; 9];
+    let mut last_index = [0; 9];
+    for i in sudoku.house(Unit::new(g, g_idx)) {
+        for c in sudoku[i] {
+            let cc = c.trailing_zeros() as usize;
+            counts[cc] += 1;
+            last_index[cc] = i;
+        }
+    }
 
-                for (c_idx, count) in counts.iter().enumerate() {
-                    if *count == 1 {
-                        let s_idx = last_index[c_idx];
-                        if self[s_idx].num_candidates() != 1 {
-                            self[s_idx] = CandidateSet::new(1 << c_idx);
-                            self[s_idx].set_solved();
-                            solved += 1;
-                        }
-                    }
-                }
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(c_idx, count)| **count == 1 && sudoku[last_index[*c_idx]].num_candidates() != 1)
+        .map(|(c_idx, _)| {
+            let s_idx = last_index[c_idx];
+            Step {
+                technique: Technique::HiddenSingle,
+                units: vec![Unit::new(g, g_idx)],
+                cells: vec![s_idx],
+                digits: vec![c_idx + 1],
+                eliminations: vec![],
+                placements: vec![Candidate::new(s_idx, c_idx + 1)],
+                links: vec![],
+                sets: vec![],
             }
-        }
-        solved
+        })
+        .collect()
+}
+
+impl HiddenSingles for Sudoku {
+    fn find_hidden_in(&self, g: Grouping, g_idx: usize) -> Vec<Step> {
+        hidden_in_unit(self, g, g_idx)
+    }
+
+    fn find_hidden(&self) -> impl Iterator<Item = Step> + '_ {
+        // Every row, col, box, and then the extra regions.
+        self.houses()
+            .flat_map(move |u| self.find_hidden_in(u.grouping, u.index))
     }
 }
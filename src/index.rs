@@ -1,19 +1,42 @@
 use crate::fast_index;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Grouping {
     Row,
     Column,
     Box,
+    /// One of the puzzle's extra regions, numbered by its place
+    /// in `Sudoku::regions`.
+    Region,
 }
 
+/// The houses every puzzle has. Extra regions come from the puzzle.
 pub const ALL_GROUPINGS: [Grouping; 3] = [Grouping::Row, Grouping::Column, Grouping::Box];
 
+const REGION_CELLS: &str = "The cells of a Region belong to the puzzle, see Sudoku::house";
+
 impl Grouping {
+    /// The positions of row, column, or box `i`.
+    ///
+    /// Panics for `Grouping::Region`, use `Sudoku::house` instead.
     pub fn iter(&self, i: usize) -> impl std::iter::Iterator<Item = usize> {
         match *self {
             Grouping::Row => fast_index::row_iter(i),
             Grouping::Column => fast_index::column_iter(i),
             Grouping::Box => fast_index::box_iter(i),
+            Grouping::Region => panic!("{}", REGION_CELLS),
+        }
+    }
+    /// The positions of row, column, or box `i` in a grid
+    /// with `digits` digits, see `BoxSize`.
+    ///
+    /// Panics for `Grouping::Region`, use `Sudoku::house` instead.
+    pub fn sized_iter(&self, digits: usize, i: usize) -> impl std::iter::Iterator<Item = usize> {
+        match *self {
+            Grouping::Row => fast_index::sized_row_iter(digits, i),
+            Grouping::Column => fast_index::sized_column_iter(digits, i),
+            Grouping::Box => fast_index::sized_box_iter(digits, i),
+            Grouping::Region => panic!("{}", REGION_CELLS),
         }
     }
     pub fn sub_iter(
@@ -25,20 +48,27 @@ impl Grouping {
             Grouping::Row => fast_index::row_comb_iter(i, subset_size),
             Grouping::Column => fast_index::column_comb_iter(i, subset_size),
             Grouping::Box => fast_index::box_comb_iter(i, subset_size),
+            Grouping::Region => panic!("{}", REGION_CELLS),
         }
     }
+    /// The first row of row, column, or box `i`. This is for the usual
+    /// 3x3 boxes, Jigsaw boxes don't line up so use `Sudoku::house`.
     pub fn start_row(&self, i: usize) -> usize {
         match *self {
             Grouping::Row => i,
             Grouping::Column => 0,
             Grouping::Box => (i / 3) * 3,
+            Grouping::Region => panic!("{}", REGION_CELLS),
         }
     }
+    /// The first column of row, column, or box `i`, with the
+    /// same caveat as `start_row`.
     pub fn start_column(&self, i: usize) -> usize {
         match *self {
             Grouping::Row => 0,
             Grouping::Column => i,
             Grouping::Box => (i % 3) * 3,
+            Grouping::Region => panic!("{}", REGION_CELLS),
         }
     }
 }
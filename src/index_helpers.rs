@@ -9,3 +9,14 @@ pub fn get_index_tuple(i: usize) -> IndexTuple {
     let box_i = ((row_i / 3) * 3) + (col_i / 3);
     (row_i, col_i, box_i)
 }
+
+/// Do the two positions share a row, column, or 3x3 box.
+/// A position never sees itself.
+///
+/// This only knows the classic houses. `Sudoku::sees` also covers
+/// extra regions, Jigsaw boxes, and chess moves.
+pub(crate) fn classic_sees(a: usize, b: usize) -> bool {
+    let (row_a, col_a, box_a) = get_index_tuple(a);
+    let (row_b, col_b, box_b) = get_index_tuple(b);
+    a != b && (row_a == row_b || col_a == col_b || box_a == box_b)
+}
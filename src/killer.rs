@@ -0,0 +1,383 @@
+use crate::candidate_set::{CandidateSet, CandidateSetIterator};
+use crate::step::{to_digits, Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+
+/// A Killer Sudoku cage. The digits in the cells are all different
+/// and add up to the sum.
+///
+/// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cage {
+    /// The cell indexes; 0 through 80.
+    pub cells: Vec<usize>,
+    pub sum: usize,
+}
+
+impl Cage {
+    pub fn new(sum: usize, cells: Vec<usize>) -> Self {
+        Cage { cells, sum }
+    }
+}
+
+pub trait KillerCages {
+    /// Candidates that aren't in any combination of different
+    /// digits that adds up to the cage's sum.
+    fn find_cage_combination(&self) -> impl Iterator<Item = Step> + '_;
+    /// The 45 rule. The cells of a house that aren't in
+    /// a cage inside it, the innies, add up to 45 less those cages.
+    /// If cages cover all of it then the cells that stick out, the
+    /// outies, add up to what the cages have over 45.
+    fn find_innies_outies(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+fn digit_sum(m: usize) -> usize {
+    to_digits(m).into_iter().sum()
+}
+
+/// The candidates each of `cands` can keep when they're filled with
+/// exactly the digits in `combo`, or None if they can't be.
+///
+/// Works forward and backward through the cells tracking which digits
+/// could be used so far, then a cell keeps a digit if the cells before
+/// and after it can use up the rest.
+fn combo_candidates(cands: &[usize], combo: usize) -> Option<Vec<usize>> {
+    let n = cands.len();
+    let mut forward = vec![[false; 512]; n + 1];
+    let mut backward = vec![[false; 512]; n + 1];
+    forward[0][0] = true;
+    backward[n][0] = true;
+    for k in 0..n {
+        for used in 0..512 {
+            if forward[k][used] {
+                for m in CandidateSetIterator::new(cands[k] & combo & !used) {
+                    forward[k + 1][used | m] = true;
+                }
+            }
+        }
+    }
+    if !forward[n][combo] {
+        return None;
+    }
+    for k in (0..n).rev() {
+        for used in 0..512 {
+            if backward[k + 1][used] {
+                for m in CandidateSetIterator::new(cands[k] & combo & !used) {
+                    backward[k][used | m] = true;
+                }
+            }
+        }
+    }
+    let allowed = (0..n)
+        .map(|k| {
+            let mut keep = 0;
+            for used in (0..512).filter(|u| forward[k][*u]) {
+                for m in CandidateSetIterator::new(cands[k] & combo & !used) {
+                    if backward[k + 1][combo & !used & !m] {
+                        keep |= m;
+                    }
+                }
+            }
+            keep
+        })
+        .collect();
+    Some(allowed)
+}
+
+/// The candidates that each of `cells` can keep if they're all
+/// different digits adding up to `sum`. None if that's impossible.
+pub(crate) fn cage_candidates<const N: usize>(
+    sudoku: &Sudoku<N>,
+    cells: &[usize],
+    sum: usize,
+) -> Option<Vec<usize>> {
+    let cands: Vec<usize> = cells.iter().map(|i| sudoku[*i].get_candidates()).collect();
+    let all = cands.iter().fold(0, |a, c| a | c);
+    let mut allowed = vec![0; cells.len()];
+    let mut possible = false;
+    for combo in (1..512usize)
+        .filter(|c| c.count_ones() as usize == cells.len() && c & !all == 0)
+        .filter(|c| digit_sum(*c) == sum)
+    {
+        if let Some(keep) = combo_candidates(&cands, combo) {
+            possible = true;
+            allowed.iter_mut().zip(keep).for_each(|(a, k)| *a |= k);
+        }
+    }
+    possible.then_some(allowed)
+}
+
+/// The candidates that each of `cells` can keep if they add up to
+/// `sum`, when the same digit can be used more than once.
+fn sum_candidates(sudoku: &Sudoku, cells: &[usize], sum: usize) -> Vec<usize> {
+    let low = |i: usize| sudoku[i].get_candidates().trailing_zeros() as usize + 1;
+    let high = |i: usize| 64 - sudoku[i].get_candidates().leading_zeros() as usize;
+    let lows: usize = cells.iter().map(|i| low(*i)).sum();
+    let highs: usize = cells.iter().map(|i| high(*i)).sum();
+    cells
+        .iter()
+        .map(|i| {
+            // What's left once the others are as big or as small as they can be.
+            let smallest = (sum + high(*i)).saturating_sub(highs);
+            let largest = (sum + low(*i)).saturating_sub(lows);
+            (1..=9)
+                .filter(|d| *d >= smallest && *d <= largest)
+                .fold(0, |m, d| m | 1 << (d - 1))
+        })
+        .collect()
+}
+
+/// A step removing everything from `cells` that `allowed` doesn't keep.
+fn restrict_step(
+    sudoku: &Sudoku,
+    technique: Technique,
+    units: Vec<Unit>,
+    cells: &[usize],
+    allowed: &[usize],
+) -> Option<Step> {
+    let eliminations: Vec<Candidate> = cells
+        .iter()
+        .zip(allowed)
+        .filter(|(i, _)| !sudoku[**i].is_solved())
+        .flat_map(|(i, keep)| Candidate::from_mask(*i, sudoku[*i].get_candidates() & !keep))
+        .collect();
+    if eliminations.is_empty() {
+        return None;
+    }
+    Some(Step {
+        technique,
+        units,
+        cells: cells.to_vec(),
+        digits: to_digits(allowed.iter().fold(0, |a, k| a | k)),
+        eliminations,
+        placements: vec![],
+        links: vec![],
+        sets: vec![],
+    })
+}
+
+/// Cells that have to add up to `sum`. If they all see each other the
+/// digits are different and can be treated as a cage.
+fn virtual_cage_step(
+    sudoku: &Sudoku,
+    technique: Technique,
+    unit: Unit,
+    cells: &[usize],
+    sum: usize,
+) -> Option<Step> {
+    let distinct = cells
+        .iter()
+        .enumerate()
+        .all(|(n, a)| cells[n + 1..].iter().all(|b| sudoku.sees(*a, *b)));
+    let allowed = if distinct && cells.len() <= 9 {
+        cage_candidates(sudoku, cells, sum)?
+    } else {
+        sum_candidates(sudoku, cells, sum)
+    };
+    restrict_step(sudoku, technique, vec![unit], cells, &allowed)
+}
+
+/// The innies and outies of a single house.
+fn innies_outies(sudoku: &Sudoku, unit: Unit) -> Vec<Step> {
+    let house = sudoku.house(unit);
+    let mut steps = vec![];
+    let touching: Vec<&Cage> = sudoku
+        .cages()
+        .iter()
+        .filter(|c| c.cells.iter().any(|i| house.contains(i)))
+        .collect();
+    let (inside, partly): (Vec<&Cage>, Vec<&Cage>) = touching
+        .iter()
+        .partition(|c| c.cells.iter().all(|i| house.contains(i)));
+    if !inside.is_empty() {
+        let innies: Vec<usize> = house
+            .iter()
+            .filter(|i| !inside.iter().any(|c| c.cells.contains(i)))
+            .cloned()
+            .collect();
+        let sum = 45usize.checked_sub(inside.iter().map(|c| c.sum).sum());
+        if let (false, Some(sum)) = (innies.is_empty(), sum) {
+            steps.extend(virtual_cage_step(
+                sudoku,
+                Technique::Innies,
+                unit,
+                &innies,
+                sum,
+            ));
+        }
+    }
+    let covered = house
+        .iter()
+        .all(|i| touching.iter().any(|c| c.cells.contains(i)));
+    if covered && !partly.is_empty() {
+        let outies: Vec<usize> = partly
+            .iter()
+            .flat_map(|c| c.cells.iter())
+            .filter(|i| !house.contains(i))
+            .cloned()
+            .collect();
+        let sum = touching
+            .iter()
+            .map(|c| c.sum)
+            .sum::<usize>()
+            .checked_sub(45);
+        if let Some(sum) = sum {
+            steps.extend(virtual_cage_step(
+                sudoku,
+                Technique::Outies,
+                unit,
+                &outies,
+                sum,
+            ));
+        }
+    }
+    steps
+}
+
+/// Remove every candidate that no cage combination allows.
+///
+/// Returns None if a cage can't be filled, otherwise if anything changed.
+pub(crate) fn restrict_cages<const N: usize>(sudoku: &mut Sudoku<N>) -> Option<bool> {
+    let mut changed = false;
+    for n in 0..sudoku.cages().len() {
+        let cage = &sudoku.cages()[n];
+        let cells = cage.cells.clone();
+        let allowed = cage_candidates(sudoku, &cells, cage.sum)?;
+        for (i, keep) in cells.into_iter().zip(allowed) {
+            let c = sudoku[i].get_candidates();
+            if c & !keep == 0 {
+                continue;
+            }
+            if sudoku[i].is_solved() || c & keep == 0 {
+                return None;
+            }
+            sudoku[i] = CandidateSet::new(c & keep);
+            changed = true;
+        }
+    }
+    Some(changed)
+}
+
+impl KillerCages for Sudoku {
+    fn find_cage_combination(&self) -> impl Iterator<Item = Step> + '_ {
+        self.cages().iter().filter_map(move |cage| {
+            let allowed = cage_candidates(self, &cage.cells, cage.sum)?;
+            restrict_step(
+                self,
+                Technique::CageCombination,
+                vec![],
+                &cage.cells,
+                &allowed,
+            )
+        })
+    }
+
+    fn find_innies_outies(&self) -> impl Iterator<Item = Step> + '_ {
+        // Nothing to do for classic puzzles.
+        let houses: Vec<Unit> = if self.cages().is_empty() {
+            vec![]
+        } else {
+            self.houses().collect()
+        };
+        houses.into_iter().flat_map(move |u| innies_outies(self, u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SudokuErr;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+    use crate::transform::Transform;
+
+    const EMPTY: &str =
+        ".................................................................................";
+
+    #[test]
+    fn test_combo_candidates() {
+        // 1, 2, and 3 in some order but only the last can be a 3.
+        let allowed = combo_candidates(&[0b011, 0b011, 0b111], 0b111).unwrap();
+        assert_eq!(vec![0b011, 0b011, 0b100], allowed);
+        assert!(combo_candidates(&[0b001, 0b001], 0b011).is_none());
+    }
+
+    #[test]
+    fn test_find_cage_combination() {
+        let p = parse_killer(EMPTY, "3: r1c1 r1c2\n24: r9c7 r9c8 r9c9").unwrap();
+        let steps: Vec<Step> = p.find_cage_combination().collect();
+        assert_eq!(2, steps.len());
+        assert_eq!(vec![1, 2], steps[0].digits);
+        assert_eq!(14, steps[0].eliminations.len());
+        assert_eq!(vec![7, 8, 9], steps[1].digits);
+    }
+
+    #[test]
+    fn test_find_innies() {
+        // The other eight positions of the first row add up to 41,
+        // which leaves 4 for r1c9.
+        let cages = "3: r1c1 r1c2\n38: r1c3 r1c4 r1c5 r1c6 r1c7 r1c8";
+        let p = parse_killer(EMPTY, cages).unwrap();
+        let step = p
+            .find_innies_outies()
+            .find(|s| s.technique == Technique::Innies)
+            .unwrap();
+        assert_eq!(vec![8], step.cells);
+        assert_eq!(vec![4], step.digits);
+    }
+
+    #[test]
+    fn test_try_solve_killer() {
+        let p = parse_killer(EMPTY, KILLER).unwrap();
+        assert_eq!(0, p.num_solved());
+        assert!(p.has_unique_solution());
+        let sr = p.try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.cage_combination > 0);
+        assert!(sr.innies_outies > 0);
+        assert!(!sr.guessed);
+        assert_eq!(
+            "819637425527841369643529178476218953135796284298354716351962847764183592982475631",
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_invalid_cages() {
+        let overlap = "3: r1c1 r1c2\n4: r1c2 r1c3";
+        assert!(matches!(
+            parse_killer(EMPTY, overlap),
+            Err(SudokuErr::InvalidCage())
+        ));
+        assert!(matches!(
+            parse_killer(EMPTY, "2: r1c1 r1c2"),
+            Err(SudokuErr::InvalidCage())
+        ));
+        assert!(matches!(
+            parse_cages("3 r1c1 r1c2"),
+            Err(SudokuErr::Parse())
+        ));
+        assert!(matches!(parse_cages("3: r1c0"), Err(SudokuErr::Parse())));
+        // The givens don't fit the cage.
+        let pzl =
+            "99...............................................................................";
+        assert!(parse_sudoku(pzl).is_err());
+        let pzl =
+            "5................................................................................";
+        assert!(matches!(
+            parse_killer(pzl, "3: r1c1 r1c2"),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+    }
+
+    #[test]
+    fn test_transform_moves_cages() {
+        let mut p = parse_killer(EMPTY, KILLER).unwrap();
+        p.rotate();
+        p.swap_bands(0, 2);
+        assert!(p.is_valid());
+        // r9c9 moves to r9c1 then to r3c1.
+        assert!(p.cages().contains(&Cage::new(1, vec![18])));
+        assert!(p.try_solve().is_solved);
+    }
+}
@@ -11,23 +11,68 @@ pub mod slow_index;
 // The main index entrypoint.
 mod index;
 
+mod brute_force;
 mod candidate_set;
+mod canonical;
+mod chess;
+mod dlx;
 mod error;
 mod parse;
+mod region;
 mod remove_mask;
+mod sized;
+mod step;
 mod sudoku;
+mod transform;
 
 // The modules that make up solve
+mod als;
 mod box_line;
+mod chain;
+mod coloring;
+mod finned_fish;
+mod fish;
 mod hidden_singles;
+mod killer;
+mod naked_singles;
 mod pointing_pairs;
 mod remove_candidates;
+mod single_digit;
 mod subset;
+mod uniqueness;
+mod wing;
 
 // Does the actual solving
 mod solve;
+// Buckets solve reports by difficulty
+mod difficulty;
+// Creates new puzzles
+mod generate;
+// Rates how hard a puzzle is
+mod rate;
 
+// Grids of candidates for the technique tests
+#[cfg(test)]
+mod test_helpers;
+
+pub use self::brute_force::BruteForce;
+pub use self::canonical::Canonical;
+pub use self::chess::ChessMove;
+pub use self::difficulty::Difficulty;
+pub use self::dlx::DancingLinks;
 pub use self::error::SudokuErr;
-pub use self::parse::parse_sudoku;
-pub use self::solve::{SolveReport, Solveable};
+pub use self::generate::{GenerateOptions, Generator, Symmetry};
+pub use self::index::Grouping;
+pub use self::killer::Cage;
+pub use self::parse::{
+    parse_cages, parse_hex, parse_jigsaw, parse_killer, parse_pencilmarks, parse_sized,
+    parse_sudoku, parse_sudoku_with, parse_sudoku_with_chess, parse_sudoku_with_regions,
+    ParseOptions,
+};
+pub use self::rate::{rate_step, Rate, RatedStep, Rating};
+pub use self::region::Region;
+pub use self::sized::BoxSize;
+pub use self::solve::{next_step, next_step_with, SolveOptions, SolveReport, Solveable};
+pub use self::step::{Candidate, Link, Node, Step, Technique, Unit};
 pub use self::sudoku::Sudoku;
+pub use self::transform::Transform;
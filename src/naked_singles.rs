@@ -0,0 +1,31 @@
+use crate::step::{Candidate, Step, Technique};
+use crate::sudoku::Sudoku;
+
+pub trait NakedSingles {
+    fn find_naked(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+impl NakedSingles for Sudoku {
+    fn find_naked(&self) -> impl Iterator<Item = Step> + '_ {
+        // Any position that isn't solved but only has
+        // one candidate left must be that candidate.
+        self.iter()
+            .enumerate()
+            .filter(|(_, cs)| !cs.is_solved() && cs.num_candidates() == 1)
+            .map(|(idx, cs)| {
+                let c = Candidate::from_mask(idx, cs.get_candidates())
+                    .next()
+                    .unwrap();
+                Step {
+                    technique: Technique::NakedSingle,
+                    units: vec![],
+                    cells: vec![idx],
+                    digits: vec![c.digit],
+                    eliminations: vec![],
+                    placements: vec![c],
+                    links: vec![],
+                    sets: vec![],
+                }
+            })
+    }
+}
@@ -1,9 +1,21 @@
 use super::candidate_set::CandidateSet;
+use super::chess::ChessMove;
 use super::error::SudokuErr;
+use super::killer::Cage;
+use super::region::Region;
 use super::remove_candidates::*;
 use super::sudoku::Sudoku;
 
-pub fn parse_sudoku(pzl: &str) -> Result<Sudoku, SudokuErr> {
+/// Options for `parse_sudoku_with`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Reject puzzles that don't have exactly one solution.
+    /// This means solving the puzzle by brute force so it's off by default.
+    pub require_unique: bool,
+}
+
+/// Decode the 81 positions of a puzzle without checking them.
+fn parse_positions(pzl: &str) -> Result<[CandidateSet; 81], SudokuErr> {
     // ascii only please
     if !pzl.is_ascii() {
         return Err(SudokuErr::Ascii());
@@ -22,16 +34,249 @@ pub fn parse_sudoku(pzl: &str) -> Result<Sudoku, SudokuErr> {
     if used != 81 {
         return Err(SudokuErr::Parse());
     }
-    let mut s = Sudoku::new(p);
+    Ok(p)
+}
+
+pub fn parse_sudoku(pzl: &str) -> Result<Sudoku, SudokuErr> {
+    parse_sudoku_with(pzl, ParseOptions::default())
+}
+
+pub fn parse_sudoku_with(pzl: &str, options: ParseOptions) -> Result<Sudoku, SudokuErr> {
+    let mut s = Sudoku::new(parse_positions(pzl)?);
     // After removing the impossible candidates
     // make sure that everything is still valid.
     s.remove_candidates(false);
-    if s.is_valid() {
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    if !options.require_unique {
         // Return the result if the puzzle is not provably invalid.
-        Ok(s)
-    } else {
-        Err(SudokuErr::InvalidPuzzle())
+        return Ok(s);
+    }
+    match s.count_solutions(2) {
+        0 => Err(SudokuErr::NoSolution()),
+        1 => Ok(s),
+        _ => Err(SudokuErr::MultipleSolutions()),
+    }
+}
+
+/// Parse a puzzle with `N` digits, like `parse_sized::<16>` for 16x16.
+///
+/// The digits past 9 are letters, so 16x16 uses 1 to 9 then A to G;
+/// see `parse_hex` for 0 to F. Empty positions are '.', '0', '*', or
+/// 'x' and anything else that isn't a digit or a letter is skipped.
+/// A digit or letter past the last digit of the grid is an error
+/// rather than being skipped.
+pub fn parse_sized<const N: usize>(pzl: &str) -> Result<Sudoku<N>, SudokuErr> {
+    // ascii only please
+    if !pzl.is_ascii() {
+        return Err(SudokuErr::Ascii());
+    }
+    let mut positions = Vec::with_capacity(N * N);
+    for c in pzl.chars() {
+        match CandidateSet::parse_position(c) {
+            Some(cs) => positions.push(cs),
+            None if c.is_ascii_alphanumeric() => return Err(SudokuErr::Parse()),
+            None => {}
+        }
+    }
+    check_sized(positions)
+}
+
+/// Parse a 16x16 puzzle written with the hex digits 0 to F.
+///
+/// Empty positions are '.' or '*' and anything else that isn't
+/// a hex digit or a letter is skipped.
+pub fn parse_hex(pzl: &str) -> Result<Sudoku<16>, SudokuErr> {
+    // ascii only please
+    if !pzl.is_ascii() {
+        return Err(SudokuErr::Ascii());
+    }
+    let mut positions = Vec::with_capacity(256);
+    for c in pzl.chars() {
+        match (c, c.to_digit(16)) {
+            ('.' | '*', _) => positions.push(CandidateSet::new(CandidateSet::<16>::ALL)),
+            (_, Some(d)) => {
+                let mut cs = CandidateSet::new(1 << d);
+                cs.set_solved();
+                positions.push(cs);
+            }
+            _ if c.is_ascii_alphanumeric() => return Err(SudokuErr::Parse()),
+            _ => {}
+        }
     }
+    check_sized(positions)
+}
+
+/// Make a puzzle out of every position, if there are the right
+/// number of them and it isn't broken.
+fn check_sized<const N: usize>(positions: Vec<CandidateSet<N>>) -> Result<Sudoku<N>, SudokuErr> {
+    if positions.len() != N * N {
+        return Err(SudokuErr::Parse());
+    }
+    let mut s = Sudoku::from_positions(positions);
+    s.remove_candidates(false);
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
+}
+
+/// Parse a grid of pencilmarks where every position lists its candidates.
+///
+/// Solved positions are their digit after a `+`, like
+/// `Sudoku::pencilmarks` writes them. Any other position is unsolved,
+/// even with a single candidate. Anything else that isn't a digit
+/// separates positions so box borders like the ones in
+/// `examples::MULTI_LINE` are fine. Unlike `parse_sudoku` the
+/// candidates are kept exactly as they are.
+pub fn parse_pencilmarks(pzl: &str) -> Result<Sudoku, SudokuErr> {
+    // ascii only please
+    if !pzl.is_ascii() {
+        return Err(SudokuErr::Ascii());
+    }
+
+    let mut p = [CandidateSet::default(); 81];
+    let mut used = 0;
+    // A `+` in a border like `-----+-----` isn't next to any digits.
+    for token in pzl
+        .split(|c: char| !c.is_ascii_digit() && c != '+')
+        .filter(|t| t.bytes().any(|b| b.is_ascii_digit()))
+    {
+        let (is_solved, digits) = match token.strip_prefix('+') {
+            Some(digits) => (true, digits),
+            None => (false, token),
+        };
+        let mut m: usize = 0;
+        for b in digits.bytes() {
+            // 0 isn't a digit and each digit is only listed once.
+            let d = b.wrapping_sub(b'0');
+            if !(1..=9).contains(&d) || m & (1 << (d - 1)) != 0 {
+                return Err(SudokuErr::Parse());
+            }
+            m |= 1 << (d - 1);
+        }
+        if is_solved && m.count_ones() != 1 {
+            return Err(SudokuErr::Parse());
+        }
+        if used < 81 {
+            p[used] = CandidateSet::new(m);
+            if is_solved {
+                p[used].set_solved();
+            }
+        }
+        used += 1;
+    }
+    if used != 81 {
+        return Err(SudokuErr::Parse());
+    }
+    let s = Sudoku::new(p);
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
+}
+
+/// Parse a cell in the r1c1 notation into its index.
+fn parse_cell(s: &str) -> Option<usize> {
+    let s = s.to_ascii_lowercase();
+    let (row, col) = s.strip_prefix('r')?.split_once('c')?;
+    let row: usize = row.parse().ok()?;
+    let col: usize = col.parse().ok()?;
+    if !(1..=9).contains(&row) || !(1..=9).contains(&col) {
+        return None;
+    }
+    Some((row - 1) * 9 + col - 1)
+}
+
+/// Parse Killer Sudoku cages, one per line, as the sum then the cells.
+///
+/// ```text
+/// 15: r1c1 r1c2 r2c1
+/// 7: r1c3 r1c4
+/// ```
+pub fn parse_cages(cages: &str) -> Result<Vec<Cage>, SudokuErr> {
+    cages
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (sum, cells) = l.split_once(':').ok_or(SudokuErr::Parse())?;
+            let sum = sum.trim().parse().map_err(|_| SudokuErr::Parse())?;
+            let cells = cells
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|c| !c.is_empty())
+                .map(|c| parse_cell(c).ok_or(SudokuErr::Parse()))
+                .collect::<Result<Vec<usize>, SudokuErr>>()?;
+            Ok(Cage::new(sum, cells))
+        })
+        .collect()
+}
+
+/// Parse a Killer Sudoku from its givens, often none at
+/// all, and its cages in the format of `parse_cages`.
+pub fn parse_killer(pzl: &str, cages: &str) -> Result<Sudoku, SudokuErr> {
+    let mut s = parse_sudoku(pzl)?;
+    s.set_cages(parse_cages(cages)?)?;
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
+}
+
+/// Parse a puzzle with extra houses, like the diagonals of X-Sudoku.
+pub fn parse_sudoku_with_regions(pzl: &str, regions: Vec<Region>) -> Result<Sudoku, SudokuErr> {
+    let mut s = parse_sudoku(pzl)?;
+    s.set_regions(regions)?;
+    s.remove_candidates(false);
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
+}
+
+/// Parse a puzzle where the same digit can't be a chess move apart,
+/// like Anti-Knight Sudoku.
+pub fn parse_sudoku_with_chess(pzl: &str, moves: Vec<ChessMove>) -> Result<Sudoku, SudokuErr> {
+    let mut s = parse_sudoku(pzl)?;
+    s.set_chess(moves);
+    s.remove_candidates(false);
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
+}
+
+/// Parse a Jigsaw puzzle where `layout` names the box of every position.
+///
+/// Any nine different characters can name the boxes, so a layout can
+/// look like `"111222333111222333..."`. Whitespace is skipped. The
+/// boxes are numbered in the order of their names.
+pub fn parse_jigsaw(pzl: &str, layout: &str) -> Result<Sudoku, SudokuErr> {
+    if !layout.is_ascii() {
+        return Err(SudokuErr::Ascii());
+    }
+    let chars: Vec<char> = layout.chars().filter(|c| !c.is_whitespace()).collect();
+    // Number the boxes in the order of their names.
+    let mut names = chars.clone();
+    names.sort();
+    names.dedup();
+    if chars.len() != 81 || names.len() != 9 {
+        return Err(SudokuErr::Parse());
+    }
+    let mut boxes = [0; 81];
+    for (b, c) in boxes.iter_mut().zip(chars) {
+        *b = names.binary_search(&c).unwrap();
+    }
+    // The usual boxes don't apply so the candidates can only be
+    // worked out once the layout is in place.
+    let mut s = Sudoku::new(parse_positions(pzl)?);
+    s.set_jigsaw(boxes)?;
+    s.remove_candidates(false);
+    if !s.is_valid() {
+        return Err(SudokuErr::InvalidPuzzle());
+    }
+    Ok(s)
 }
 
 #[cfg(test)]
@@ -71,4 +316,91 @@ mod tests {
         let p = parse_sudoku(s);
         assert!(p.is_err());
     }
+
+    #[test]
+    fn test_parse_pencilmarks() {
+        let s = "
+            .-----------------.-----------------.-----------------.
+            | 1   2   3       | 4   5   6       | 7   8   9       |
+            | 4   5   6       | 7   8   9       | 1   2   3       |
+            | 7   8   9       | 1   2   3       | 4   5   6       |
+            :-----------------+-----------------+-----------------:
+            | 2   1   4       | 3   6   5       | 8   9   7       |
+            | 3   6   5       | 8   9   7       | 2   1   4       |
+            | 8   9   7       | 2   1   4       | 3   6   5       |
+            :-----------------+-----------------+-----------------:
+            | 5   3   1       | 6   4   2       | 9   7   8       |
+            | 6   4   2       | 9   7   8       | 5   3   1       |
+            | 9   7   8       | 5   3   1       | 6   4   2       |
+            '-----------------'-----------------'-----------------'";
+        // Without a `+` they're the only candidate, not solved.
+        let p = parse_pencilmarks(s).unwrap();
+        assert_eq!(0, p.num_solved());
+        assert!((0..81).all(|i| p[i].num_candidates() == 1));
+        let p = parse_pencilmarks(&s.replace(" 1", " +1")).unwrap();
+        assert_eq!(9, p.num_solved());
+        assert_eq!(Some(1), p[0].value());
+
+        let s = "
+            19 +2 +3 | +4 +5 +6 | +7 +8 19
+            +4 +5 +6 | +7 +8 +9 | +1 +2 +3
+            +7 +8 19 | +1 +2 +3 | +4 +5 +6
+            ---------|----------|---------
+            +2 +1 +4 | +3 +6 +5 | +8 +9 +7
+            +3 +6 +5 | +8 +9 +7 | +2 +1 +4
+            +8 +9 +7 | +2 +1 +4 | +3 +6 +5
+            ---------|----------|---------
+            +5 +3 +1 | +6 +4 +2 | +9 +7 +8
+            +6 +4 +2 | +9 +7 +8 | +5 +3 +1
+            +9 +7 +8 | +5 +3 +1 | +6 +4 +2";
+        let p = parse_pencilmarks(s).unwrap();
+        assert!(!p.is_solved());
+        assert_eq!(78, p.num_solved());
+        assert_eq!(2, p[0].num_candidates());
+        // The extra 9 in r1c1 isn't removed.
+        assert!(!p[0].is_solved());
+        assert_eq!(1 | 1 << 8, p[0].get());
+    }
+
+    #[test]
+    fn test_parse_pencilmarks_errors() {
+        assert!(parse_pencilmarks("123").is_err());
+        // 0 is never a candidate.
+        let s = "10 ".to_string() + &"1 ".repeat(80);
+        assert!(matches!(parse_pencilmarks(&s), Err(SudokuErr::Parse())));
+        // Every digit once.
+        let s = "11 ".to_string() + &"123456789 ".repeat(80);
+        assert!(matches!(parse_pencilmarks(&s), Err(SudokuErr::Parse())));
+        // A solved position has a single digit.
+        let s = "+12 ".to_string() + &"123456789 ".repeat(80);
+        assert!(matches!(parse_pencilmarks(&s), Err(SudokuErr::Parse())));
+        // Two 1s solved in the first row.
+        let s = "+1 +1 ".to_string() + &"123456789 ".repeat(79);
+        assert!(matches!(
+            parse_pencilmarks(&s),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+    }
+
+    #[test]
+    fn test_require_unique() {
+        let options = ParseOptions {
+            require_unique: true,
+        };
+        assert!(parse_sudoku_with(ONE_LINE, options).is_ok());
+        // A solved grid missing a rectangle where the digits can swap.
+        let s = "8196.7.255278.1.69643529178476218953135796284298354716351962847764183592982475631";
+        assert!(parse_sudoku(s).is_ok());
+        assert!(matches!(
+            parse_sudoku_with(s, options),
+            Err(SudokuErr::MultipleSolutions())
+        ));
+        // r1c8 and r1c9 can only be 9.
+        let s = "123456............................7.........8................8.........7.........";
+        assert!(parse_sudoku(s).is_ok());
+        assert!(matches!(
+            parse_sudoku_with(s, options),
+            Err(SudokuErr::NoSolution())
+        ));
+    }
 }
@@ -1,87 +1,116 @@
 use crate::index::Grouping;
 use crate::index_helpers::*;
-use crate::remove_mask::*;
+use crate::step::{to_digits, Candidate, Step, Technique, Unit};
 use crate::sudoku::Sudoku;
 
 pub trait Pointing {
-    fn handle_pointing(&mut self) -> (usize, usize);
+    fn find_pointing(&self) -> impl Iterator<Item = Step> + '_;
 }
 
-impl Pointing for Sudoku {
-    fn handle_pointing(&mut self) -> (usize, usize) {
-        let g = Grouping::Box;
-        (0..9)
-            .find_map(|box_i| {
-                let start_row = g.start_row(box_i);
-                let start_col = g.start_column(box_i);
-                let mut row_count = [0; 9];
-                // Mask of the candidates that are in this row
-                let mut row_mask: [usize; 3] = [0; 3];
-                // Mask of candidates that are in other rows
-                let mut row_mask_other: [usize; 3] = [0; 3];
-                // Mask of the candidates that are in this column
-                let mut col_mask: [usize; 3] = [0; 3];
-                // Mask of candidates that are in other columns
-                let mut col_mask_other: [usize; 3] = [0; 3];
+/// Build the step for candidates in `only` that are confined to
+/// the line `line_g`/`line` inside of `box_i`.
+fn pointing_step(
+    sudoku: &Sudoku,
+    box_i: usize,
+    line_g: Grouping,
+    line: usize,
+    only: usize,
+) -> Option<Step> {
+    let in_box = |i: &usize| sudoku.box_of(*i) == box_i;
+    let eliminations: Vec<Candidate> = line_g
+        .iter(line)
+        .filter(|i| !in_box(i) && !sudoku[*i].is_solved())
+        .flat_map(|i| Candidate::from_mask(i, sudoku[i].get() & only))
+        .collect();
+    if eliminations.is_empty() {
+        return None;
+    }
+    let cells = line_g
+        .iter(line)
+        .filter(|i| in_box(i) && !sudoku[*i].is_solved() && sudoku[*i].get() & only != 0)
+        .collect();
+    Some(Step {
+        technique: Technique::Pointing,
+        units: vec![Unit::new(Grouping::Box, box_i), Unit::new(line_g, line)],
+        cells,
+        digits: to_digits(only),
+        eliminations,
+        placements: vec![],
+        links: vec![],
+        sets: vec![],
+    })
+}
 
-                // Copy down the candidates that are
-                // in each row or col of this box.
-                for idx in g.iter(box_i) {
-                    let (row, col, _bi) = get_index_tuple(idx);
-                    // Skip the solved one. Those are
-                    // handled by remove_candidates.
-                    if self[idx].is_solved() {
-                        continue;
-                    }
+/// The candidates in `masks[idx]` that aren't in any of the others.
+fn only_in(masks: &[usize; 9], idx: usize) -> usize {
+    let other = masks
+        .iter()
+        .enumerate()
+        .filter(|(n, _)| *n != idx)
+        .fold(0, |a, (_, m)| a | m);
+    masks[idx] & !other
+}
 
-                    let m = self[idx].get();
-                    row_mask[row - start_row] |= m;
-                    col_mask[col - start_col] |= m;
-                    row_count[row] += 1;
-                }
+fn pointing_in_box(sudoku: &Sudoku, box_i: usize) -> Vec<Step> {
+    // Mask of the candidates that are in each row
+    let mut row_mask: [usize; 9] = [0; 9];
+    // Mask of the candidates that are in each column
+    let mut col_mask: [usize; 9] = [0; 9];
+    // The rows and columns that the box is in.
+    let mut rows: Vec<usize> = vec![];
+    let mut cols: Vec<usize> = vec![];
 
-                let mut changed = 0;
-                for idx in 0..3 {
-                    for other_idx in 0..3 {
-                        if idx == other_idx {
-                            continue;
-                        }
-                        row_mask_other[other_idx] |= row_mask[idx];
-                        col_mask_other[other_idx] |= col_mask[idx];
-                    }
-                }
+    // Copy down the candidates that are in each row or col of
+    // this box. Jigsaw boxes can spread over any of them.
+    for idx in sudoku.house(Unit::new(Grouping::Box, box_i)) {
+        let (row, col, _bi) = get_index_tuple(idx);
+        if !rows.contains(&row) {
+            rows.push(row);
+        }
+        if !cols.contains(&col) {
+            cols.push(col);
+        }
+        // Skip the solved one. Those are
+        // handled by remove_candidates.
+        if sudoku[idx].is_solved() {
+            continue;
+        }
 
-                for idx in 0..3 {
-                    let only_row = row_mask[idx] & !row_mask_other[idx];
-                    let only_col = col_mask[idx] & !col_mask_other[idx];
+        let m = sudoku[idx].get();
+        row_mask[row] |= m;
+        col_mask[col] |= m;
+    }
 
-                    if only_row != 0 {
-                        let row = start_row + idx;
-                        assert!(only_row.count_ones() <= 3);
-                        let iter = Grouping::Row.iter(row).filter(|i| {
-                            let (_, _, b) = get_index_tuple(*i);
-                            b != box_i
-                        });
+    rows.sort();
+    cols.sort();
 
-                        changed += self.remove_mask(only_row, iter);
-                    }
-                    if only_col != 0 {
-                        let col = start_col + idx;
-                        assert!(only_col.count_ones() <= 3);
-                        let iter = Grouping::Column.iter(col).filter(|i| {
-                            let (_, _, b) = get_index_tuple(*i);
-                            b != box_i
-                        });
+    // Take the rows and columns in turn.
+    let mut steps = Vec::new();
+    for idx in 0..rows.len().max(cols.len()) {
+        if let Some(row) = rows.get(idx) {
+            let only_row = only_in(&row_mask, *row);
+            if only_row != 0 {
+                steps.extend(pointing_step(sudoku, box_i, Grouping::Row, *row, only_row));
+            }
+        }
+        if let Some(col) = cols.get(idx) {
+            let only_col = only_in(&col_mask, *col);
+            if only_col != 0 {
+                steps.extend(pointing_step(
+                    sudoku,
+                    box_i,
+                    Grouping::Column,
+                    *col,
+                    only_col,
+                ));
+            }
+        }
+    }
+    steps
+}
 
-                        changed += self.remove_mask(only_col, iter);
-                    }
-                }
-                if changed > 0 {
-                    Some((changed, 0))
-                } else {
-                    None
-                }
-            })
-            .unwrap_or((0, 0))
+impl Pointing for Sudoku {
+    fn find_pointing(&self) -> impl Iterator<Item = Step> + '_ {
+        (0..9).flat_map(move |box_i| pointing_in_box(self, box_i))
     }
 }
@@ -0,0 +1,397 @@
+use crate::als::AlmostLockedSets;
+use crate::box_line::LineBoxReduction;
+use crate::chain::Chains;
+use crate::coloring::Coloring;
+use crate::finned_fish::FinnedFish;
+use crate::fish::Fish;
+use crate::hidden_singles::HiddenSingles;
+use crate::index::Grouping;
+use crate::killer::KillerCages;
+use crate::naked_singles::NakedSingles;
+use crate::pointing_pairs::Pointing;
+use crate::single_digit::SingleDigit;
+use crate::solve::SolveOptions;
+use crate::step::{Step, Technique};
+use crate::subset::FindSubset;
+use crate::sudoku::Sudoku;
+use crate::uniqueness::Uniqueness;
+use crate::wing::Wing;
+
+/// A single step of a rated solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatedStep {
+    pub rating: f32,
+    pub step: Step,
+}
+
+/// How hard a puzzle is on the Sudoku Explainer scale.
+///
+/// http://sudopedia.enjoysudoku.com/SE.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// The rating of the hardest step. This is the puzzle's rating.
+    pub max: f32,
+    /// Did the techniques solve the puzzle. If not then `max`
+    /// only covers the steps that were found.
+    pub is_solved: bool,
+    /// Every step in the order it was applied.
+    pub steps: Vec<RatedStep>,
+}
+
+pub trait Rate {
+    fn rate(&self) -> Rating;
+    fn rate_with(&self, options: SolveOptions) -> Rating;
+}
+
+type Finder = for<'a> fn(&'a Sudoku) -> Box<dyn Iterator<Item = Step> + 'a>;
+
+/// Every technique along with the lowest rating it can have, easiest first.
+const FINDERS: [(u32, bool, Finder); 25] = [
+    (12, false, |s| Box::new(s.find_hidden())),
+    // Direct Pointing, Direct Claiming, and Direct Hidden Pair.
+    (17, false, |s| Box::new(s.find_pointing())),
+    (19, false, |s| Box::new(s.find_box_line())),
+    (20, false, |s| Box::new(s.find_subset())),
+    (23, false, |s| Box::new(s.find_naked())),
+    (24, false, |s| Box::new(s.find_cage_combination())),
+    (27, false, |s| Box::new(s.find_innies_outies())),
+    (32, false, |s| Box::new(s.find_fish())),
+    (34, false, |s| Box::new(s.find_finned_fish())),
+    (40, false, |s| Box::new(s.find_skyscraper())),
+    (41, false, |s| Box::new(s.find_two_string_kite())),
+    (42, false, |s| Box::new(s.find_turbot_fish())),
+    (42, false, |s| Box::new(s.find_xy_wing())),
+    (44, false, |s| Box::new(s.find_xyz_wing())),
+    (45, false, |s| Box::new(s.find_empty_rectangle())),
+    (45, true, |s| Box::new(s.find_unique_rectangle())),
+    (56, true, |s| Box::new(s.find_bug_plus_one())),
+    (65, false, |s| Box::new(s.find_simple_coloring())),
+    (66, false, |s| Box::new(s.find_multi_coloring())),
+    (66, false, |s| Box::new(s.find_x_chain())),
+    (66, false, |s| Box::new(s.find_xy_chain())),
+    (70, false, |s| Box::new(s.find_aic())),
+    (75, false, |s| Box::new(s.find_als_xz())),
+    (80, false, |s| Box::new(s.find_als_xy_wing())),
+    (85, false, |s| Box::new(s.find_als_chain())),
+];
+
+/// The extra difficulty of a chain of `length` links, in tenths.
+///
+/// The same as Sudoku Explainer: every time the length passes the
+/// ceiling it goes up by 0.1, and the ceiling alternates between
+/// growing by 3/2 and 4/3.
+fn length_tenths(length: usize) -> u32 {
+    let mut added = 0;
+    let mut ceil = 4;
+    let mut is_odd = false;
+    while length > ceil {
+        added += 1;
+        ceil = if is_odd { ceil * 4 / 3 } else { ceil * 3 / 2 };
+        is_odd = !is_odd;
+    }
+    added
+}
+
+fn subset_tenths(size: usize, pair: u32, triple: u32, quad: u32) -> u32 {
+    match size {
+        2 => pair,
+        3 => triple,
+        _ => quad,
+    }
+}
+
+/// Do the step's eliminations leave a hidden single for one of the
+/// digits they take away, in a house where they took it away.
+///
+/// Sudoku Explainer calls Pointing, Claiming, and Hidden Pairs and
+/// Triples that do this direct and rates them lower, because they
+/// only need to be spotted to place a digit.
+fn is_direct(sudoku: &Sudoku, step: &Step) -> bool {
+    let mut after = sudoku.clone();
+    step.apply_exact(&mut after);
+    step.eliminations.iter().any(|c| {
+        let m = c.mask();
+        sudoku
+            .houses()
+            .map(|u| sudoku.house(u))
+            .filter(|cells| cells.contains(&c.index))
+            .any(|cells| {
+                let places = cells
+                    .iter()
+                    .filter(|i| !after[**i].is_solved() && after[**i].get() & m != 0)
+                    .count();
+                let solved = cells
+                    .iter()
+                    .any(|i| after[*i].is_solved() && after[*i].get() & m != 0);
+                places == 1 && !solved
+            })
+    })
+}
+
+/// The rating of a step found in `sudoku`, in tenths.
+///
+/// Techniques that Sudoku Explainer has use its ratings, named the
+/// way it names them. It doesn't have the rest, so they're
+/// extensions. Those sit next to the Sudoku Explainer technique
+/// they're closest to in how hard they are to spot.
+///
+/// http://sudopedia.enjoysudoku.com/SE.html
+fn step_tenths(sudoku: &Sudoku, step: &Step) -> u32 {
+    let direct = || is_direct(sudoku, step);
+    match step.technique {
+        // SE: Hidden Single, 1.2 in a block and 1.5 in a row or column.
+        Technique::HiddenSingle => match step.units.first().map(|u| u.grouping) {
+            Some(Grouping::Box) => 12,
+            _ => 15,
+        },
+        // SE: Naked Single.
+        Technique::NakedSingle => 23,
+        // SE: Direct Pointing or Pointing.
+        Technique::Pointing if direct() => 17,
+        Technique::Pointing => 26,
+        // SE: Direct Claiming or Claiming.
+        Technique::BoxLine if direct() => 19,
+        Technique::BoxLine => 28,
+        // SE: Naked Pair, Naked Triplet, and Naked Quad.
+        Technique::NakedSubset(s) => subset_tenths(s, 30, 36, 50),
+        // SE: Direct Hidden Pair and Direct Hidden Triplet.
+        Technique::HiddenSubset(2) if direct() => 20,
+        Technique::HiddenSubset(3) if direct() => 25,
+        // SE: Hidden Pair, Hidden Triplet, and Hidden Quad.
+        Technique::HiddenSubset(s) => subset_tenths(s, 34, 40, 54),
+        // SE: X-Wing, Swordfish, and Jellyfish.
+        Technique::Fish(s) => subset_tenths(s, 32, 38, 52),
+        // SE: XY-Wing and XYZ-Wing.
+        Technique::XYWing => 42,
+        Technique::XYZWing => 44,
+        // SE: Unique Rectangle types 1, 2, and 4, and type 3 where the
+        // naked subset adds a tenth.
+        Technique::UniqueRectangle(1 | 2 | 4) => 45,
+        Technique::UniqueRectangle(3) => 46,
+        // SE: Bivalue Universal Grave + 1.
+        Technique::BugPlusOne => 56,
+        // SE: Forcing X-Chain and Forcing Chain, plus the length of the
+        // chain. An XY-Chain is rated like a Forcing X-Chain.
+        Technique::XChain | Technique::XYChain => 66 + length_tenths(step.links.len()),
+        Technique::AlternatingInferenceChain => 70 + length_tenths(step.links.len()),
+        // Extension: Killer cages. Cage combinations sit between Naked
+        // Single and Direct Hidden Triplet, innies and outies between
+        // Pointing and Claiming.
+        Technique::CageCombination => 24,
+        Technique::Innies | Technique::Outies => 27,
+        // Extension: a fish and one more candidate, a tenth or two
+        // over the fish.
+        Technique::FinnedFish(s) => subset_tenths(s, 34, 40, 54),
+        Technique::SashimiFish(s) => subset_tenths(s, 35, 41, 55),
+        // Extension: short single digit chains, which Sudoku Explainer
+        // finds as X-Cycles. They're rated alongside the wings.
+        Technique::Skyscraper => 40,
+        Technique::TwoStringKite => 41,
+        Technique::TurbotFish => 42,
+        Technique::EmptyRectangle => 45,
+        // Extension: the Unique Rectangle types Sudoku Explainer doesn't
+        // have, with type 3.
+        Technique::UniqueRectangle(_) | Technique::HiddenUniqueRectangle => 46,
+        // Extension: coloring finds X-Cycles a different way, so it
+        // starts from SE's Bidirectional X-Cycle.
+        Technique::ColorTrap | Technique::ColorWrap => 65 + length_tenths(step.links.len()),
+        Technique::MultiColoring => 66 + length_tenths(step.links.len()),
+        // Extension: Almost Locked Sets, over the Forcing Chains they
+        // can stand in for.
+        Technique::AlsXZ => 75,
+        Technique::AlsXYWing => 80,
+        Technique::AlsChain => 85 + length_tenths(step.sets.len()),
+    }
+}
+
+/// The Sudoku Explainer style rating of a step found in `sudoku`.
+///
+/// Pointing, Box/Line Reductions, and Hidden Pairs and Triples rate
+/// lower when they're direct, which depends on the rest of the grid.
+/// Chains get harder as they get longer.
+pub fn rate_step(sudoku: &Sudoku, step: &Step) -> f32 {
+    step_tenths(sudoku, step) as f32 / 10.0
+}
+
+/// Is `technique` one Sudoku Explainer doesn't have.
+fn is_extension(technique: Technique) -> bool {
+    matches!(
+        technique,
+        Technique::CageCombination
+            | Technique::Innies
+            | Technique::Outies
+            | Technique::FinnedFish(_)
+            | Technique::SashimiFish(_)
+            | Technique::Skyscraper
+            | Technique::TwoStringKite
+            | Technique::TurbotFish
+            | Technique::EmptyRectangle
+            | Technique::UniqueRectangle(5..)
+            | Technique::HiddenUniqueRectangle
+            | Technique::ColorTrap
+            | Technique::ColorWrap
+            | Technique::MultiColoring
+            | Technique::AlsXZ
+            | Technique::AlsXYWing
+            | Technique::AlsChain
+    )
+}
+
+/// The easiest step available whose technique passes `allowed`, along
+/// with its rating in tenths.
+fn easiest_allowed(
+    sudoku: &Sudoku,
+    options: SolveOptions,
+    allowed: impl Fn(Technique) -> bool,
+) -> Option<(u32, Step)> {
+    let mut best: Option<(u32, Step)> = None;
+    for (min, needs_uniqueness, finder) in FINDERS {
+        if best.as_ref().is_some_and(|(r, _)| *r <= min) {
+            break;
+        }
+        if needs_uniqueness && !options.uniqueness {
+            continue;
+        }
+        for step in finder(sudoku).filter(|s| allowed(s.technique)) {
+            let r = step_tenths(sudoku, &step);
+            if best.as_ref().is_none_or(|(b, _)| r < *b) {
+                best = Some((r, step));
+            }
+            // Nothing later from this technique can be any easier.
+            if r <= min {
+                break;
+            }
+        }
+    }
+    best
+}
+
+/// The easiest step available, along with its rating in tenths.
+///
+/// Extensions are only used when no Sudoku Explainer technique
+/// applies. They often make the same eliminations as a harder
+/// technique, so taking them first would rate puzzles lower than
+/// Sudoku Explainer does.
+fn easiest_step(sudoku: &Sudoku, options: SolveOptions) -> Option<(u32, Step)> {
+    easiest_allowed(sudoku, options, |t| !is_extension(t))
+        .or_else(|| easiest_allowed(sudoku, options, |_| true))
+}
+
+impl Rate for Sudoku {
+    fn rate(&self) -> Rating {
+        self.rate_with(SolveOptions::default())
+    }
+
+    /// Solve the puzzle always taking the easiest step available, even
+    /// if a harder technique comes earlier in `next_step`'s order.
+    fn rate_with(&self, options: SolveOptions) -> Rating {
+        let mut sudoku = self.clone();
+        let mut steps = vec![];
+        let mut max = 0;
+        while let Some((r, step)) = easiest_step(&sudoku, options) {
+            step.apply(&mut sudoku);
+            max = max.max(r);
+            steps.push(RatedStep {
+                rating: r as f32 / 10.0,
+                step,
+            });
+        }
+        Rating {
+            max: max as f32 / 10.0,
+            is_solved: sudoku.is_solved(),
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+
+    #[test]
+    fn test_length_tenths() {
+        assert_eq!(0, length_tenths(3));
+        assert_eq!(0, length_tenths(4));
+        assert_eq!(1, length_tenths(5));
+        assert_eq!(1, length_tenths(6));
+        assert_eq!(2, length_tenths(7));
+        assert_eq!(3, length_tenths(9));
+        assert_eq!(4, length_tenths(13));
+    }
+
+    #[test]
+    fn test_rate_easy() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let rating = p.rate();
+        assert!(rating.is_solved);
+        let placed: usize = rating.steps.iter().map(|s| s.step.placements.len()).sum();
+        assert_eq!(81 - p.num_solved(), placed);
+        assert!(rating.max <= 2.3);
+        assert!(rating.steps.iter().all(|s| s.rating <= rating.max));
+        assert_eq!(
+            rating.max,
+            rating.steps.iter().map(|s| s.rating).fold(0.0, f32::max)
+        );
+    }
+
+    #[test]
+    fn test_rate_easiest_first() {
+        let p = parse_sudoku(HARD).unwrap();
+        let rating = p.rate();
+        assert!(rating.is_solved);
+        assert!(rating.max > 2.3);
+        // A box hidden single is always the easiest step there is.
+        for (n, s) in rating.steps.iter().enumerate() {
+            if s.rating > 1.2 {
+                let mut q = p.clone();
+                for earlier in rating.steps[..n].iter() {
+                    earlier.step.apply(&mut q);
+                }
+                assert!(q
+                    .find_hidden()
+                    .all(|h| h.units[0].grouping != Grouping::Box));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate_se() {
+        // Ratings Sudoku Explainer's table gives these puzzles.
+        let puzzles: Vec<&str> = PUZZLES.lines().collect();
+        for (n, se) in [(1, 2.3), (2, 4.2), (3, 2.3), (4, 2.3), (7, 2.3), (9, 4.2)] {
+            let rating = parse_sudoku(puzzles[n]).unwrap().rate();
+            assert!(rating.is_solved);
+            assert_eq!(se, rating.max, "puzzle {n}");
+        }
+        // HARD has a finned X-Wing, but Sudoku Explainer needs a
+        // Forcing X-Chain for it.
+        let rating = parse_sudoku(HARD).unwrap().rate();
+        assert_eq!(6.6, rating.max);
+        assert!(rating.steps.iter().all(|s| !is_extension(s.step.technique)));
+        // Direct Pointing and Direct Hidden Pair both come up on the way.
+        assert!(rating.steps.iter().any(|s| s.rating == 1.7));
+        assert!(rating.steps.iter().any(|s| s.rating == 2.0));
+    }
+
+    #[test]
+    fn test_rate_extensions() {
+        // Nothing Sudoku Explainer has can start on an empty Killer.
+        let p = parse_killer(&".".repeat(81), KILLER).unwrap();
+        let (_, step) = easiest_step(&p, SolveOptions::default()).unwrap();
+        assert!(is_extension(step.technique));
+    }
+
+    #[test]
+    fn test_rate_lots() {
+        for x in PUZZLES.lines() {
+            let p = parse_sudoku(x).unwrap();
+            let rating = p.rate();
+            assert!(rating.is_solved);
+            assert!(rating.max >= 1.2 && rating.max < 10.0);
+            assert_eq!(p.clone().try_solve().is_solved, rating.is_solved);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::index_helpers::to_index;
+
+/// An extra house on top of the rows, columns, and boxes. Like them
+/// it has nine positions that hold every digit once.
+///
+/// Steps name a region with `Grouping::Region` and its place in
+/// `Sudoku::regions`.
+///
+/// http://sudopedia.enjoysudoku.com/Variants.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The cell indexes; 0 through 80.
+    pub cells: [usize; 9],
+}
+
+impl Region {
+    pub fn new(cells: [usize; 9]) -> Self {
+        Region { cells }
+    }
+
+    /// The diagonal from r1c1 to r9c9.
+    pub fn diagonal() -> Self {
+        Region::new(std::array::from_fn(|n| to_index(n, n)))
+    }
+
+    /// The diagonal from r1c9 to r9c1.
+    pub fn anti_diagonal() -> Self {
+        Region::new(std::array::from_fn(|n| to_index(n, 8 - n)))
+    }
+
+    /// Both diagonals, as in X-Sudoku.
+    ///
+    /// http://sudopedia.enjoysudoku.com/X-Sudoku.html
+    pub fn x_sudoku() -> Vec<Self> {
+        vec![Region::diagonal(), Region::anti_diagonal()]
+    }
+
+    /// The four windows of Windoku, also called Hyper Sudoku, with
+    /// their top left corners at r2c2, r2c6, r6c2, and r6c6.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Windoku.html
+    pub fn windoku() -> Vec<Self> {
+        [(1, 1), (1, 5), (5, 1), (5, 5)]
+            .into_iter()
+            .map(|(row, col)| {
+                Region::new(std::array::from_fn(|n| to_index(row + n / 3, col + n % 3)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::BruteForce;
+    use crate::error::SudokuErr;
+    use crate::examples::*;
+    use crate::hidden_singles::HiddenSingles;
+    use crate::index::Grouping;
+    use crate::parse::*;
+    use crate::solve::{next_step, Solveable};
+    use crate::transform::Transform;
+
+    #[test]
+    fn test_regions() {
+        assert_eq!(
+            [0, 10, 20, 30, 40, 50, 60, 70, 80],
+            Region::diagonal().cells
+        );
+        assert_eq!(
+            [8, 16, 24, 32, 40, 48, 56, 64, 72],
+            Region::anti_diagonal().cells
+        );
+        let windows = Region::windoku();
+        assert_eq!(4, windows.len());
+        assert_eq!([10, 11, 12, 19, 20, 21, 28, 29, 30], windows[0].cells);
+        assert_eq!([50, 51, 52, 59, 60, 61, 68, 69, 70], windows[3].cells);
+    }
+
+    #[test]
+    fn test_invalid_region() {
+        let empty = ".".repeat(81);
+        let twice = Region::new([0, 1, 2, 3, 4, 5, 6, 7, 7]);
+        assert!(matches!(
+            parse_sudoku_with_regions(&empty, vec![twice]),
+            Err(SudokuErr::InvalidRegion())
+        ));
+        let off_grid = Region::new([0, 1, 2, 3, 4, 5, 6, 7, 81]);
+        assert!(matches!(
+            parse_sudoku_with_regions(&empty, vec![off_grid]),
+            Err(SudokuErr::InvalidRegion())
+        ));
+        // Two 1s on the diagonal, at r1c1 and r5c5.
+        let pzl = format!("1{}1{}", ".".repeat(39), ".".repeat(40));
+        assert!(parse_sudoku(&pzl).is_ok());
+        assert!(matches!(
+            parse_sudoku_with_regions(&pzl, Region::x_sudoku()),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+    }
+
+    #[test]
+    fn test_x_sudoku() {
+        assert_eq!(2, parse_sudoku(X_SUDOKU).unwrap().count_solutions(2));
+        let classic = parse_sudoku(X_SUDOKU).unwrap();
+        let p = parse_sudoku_with_regions(X_SUDOKU, Region::x_sudoku()).unwrap();
+        // The diagonals take away candidates.
+        assert!((0..81).any(|i| p[i].get_candidates() != classic[i].get_candidates()));
+        assert!(p.has_unique_solution());
+        let sr = p.try_solve();
+        assert!(sr.is_solved && !sr.guessed);
+        assert_eq!(
+            "468715329791823654325496187536187942842569731917342568674231895183954276259678413",
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_windoku() {
+        assert_eq!(2, parse_sudoku(WINDOKU).unwrap().count_solutions(2));
+        let mut p = parse_sudoku_with_regions(WINDOKU, Region::windoku()).unwrap();
+        assert!(p.has_unique_solution());
+        let q = p.clone();
+        // Somewhere along the way a window has a hidden single.
+        let mut in_window = false;
+        while let Some(step) = next_step(&p) {
+            in_window |= step.units.iter().any(|u| u.grouping == Grouping::Region);
+            step.apply(&mut p);
+        }
+        assert!(in_window);
+        assert!(p.is_solved() && p.is_valid());
+        assert_eq!(
+            "468715329721893564395462178137628945842359716956147832584231697673984251219576483",
+            p.oneline()
+        );
+        assert!((0..4).any(|idx| !q.find_hidden_in(Grouping::Region, idx).is_empty()));
+    }
+
+    #[test]
+    fn test_sparse_windoku() {
+        // Only nine givens so brute force has to guess a lot.
+        let pzl =
+            "........2..................6....3......6...............5...............9....3...5";
+        let p = parse_sudoku_with_regions(pzl, Region::windoku()).unwrap();
+        assert_eq!(2, p.count_solutions(2));
+        let solution = p.brute_force().unwrap();
+        assert!(solution.is_solved() && solution.is_valid());
+    }
+
+    #[test]
+    fn test_transform_moves_regions() {
+        let mut p = parse_sudoku_with_regions(X_SUDOKU, Region::x_sudoku()).unwrap();
+        p.rotate();
+        p.swap_bands(0, 2);
+        p.swap_stacks(0, 2);
+        assert!(p.is_valid());
+        assert!(p.has_unique_solution());
+        let mut q = parse_sudoku_with_regions(WINDOKU, Region::windoku()).unwrap();
+        q.transpose();
+        let mut moved = q.regions()[2].cells;
+        moved.sort();
+        assert_eq!(Region::windoku()[1].cells, moved);
+        assert!(q.try_solve().is_solved);
+    }
+}
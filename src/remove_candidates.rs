@@ -1,20 +1,21 @@
 use crate::candidate_set::CandidateSet;
-use crate::index_helpers::get_index_tuple;
 use crate::sudoku::Sudoku;
 
 pub trait RemoveCandidates {
     fn remove_candidates(&mut self, set_solved: bool) -> (usize, usize);
 }
 
-impl RemoveCandidates for Sudoku {
+impl<const N: usize> RemoveCandidates for Sudoku<N> {
     fn remove_candidates(&mut self, set_solved: bool) -> (usize, usize) {
-        let mut column_solved_set: [usize; 9] = [0; 9];
-        let mut row_solved_set: [usize; 9] = [0; 9];
-        let mut box_solved_set: [usize; 9] = [0; 9];
+        let mut column_solved_set: [usize; N] = [0; N];
+        let mut row_solved_set: [usize; N] = [0; N];
+        let mut box_solved_set: [usize; N] = [0; N];
+        let boxes: Vec<usize> = (0..N * N).map(|i| self.box_of(i)).collect();
         // Get the candidates that already have a single solution.
         for (i, set) in self.iter().enumerate() {
             if set.num_candidates() == 1 {
-                let (row_i, col_i, box_i) = get_index_tuple(i);
+                let (row_i, col_i) = (i / N, i % N);
+                let box_i = boxes[i];
                 // We explicitly don't use get_candidates here.
                 // The masks that we're generating will only be used for
                 // positions with num_candidates > 1 and hence no
@@ -25,15 +26,36 @@ impl RemoveCandidates for Sudoku {
                 box_solved_set[box_i] |= v;
             }
         }
+        // Extra regions are the same but a position can be in any number of them.
+        let mut region_solved_set = vec![0; N * N];
+        for region in self.regions() {
+            let v = region
+                .cells
+                .iter()
+                .filter(|i| self[**i].num_candidates() == 1)
+                .fold(0, |m, i| m | self[*i].get());
+            for i in region.cells {
+                region_solved_set[i] |= v;
+            }
+        }
+        // A solved digit can't be a chess move away either.
+        for i in (0..N * N).filter(|i| self[*i].num_candidates() == 1) {
+            let v = self[i].get();
+            for p in self.chess_peers(i) {
+                region_solved_set[p] |= v;
+            }
+        }
         let mut solved = 0;
         let mut changed = 0;
         for (i, set) in self.iter_mut().enumerate() {
-            let (row_i, col_i, box_i) = get_index_tuple(i);
+            let (row_i, col_i) = (i / N, i % N);
+            let box_i = boxes[i];
             if set.num_candidates() != 1 {
                 let new_set: usize = set.get()
                     & !row_solved_set[row_i]
                     & !column_solved_set[col_i]
-                    & !box_solved_set[box_i];
+                    & !box_solved_set[box_i]
+                    & !region_solved_set[i];
                 // If there has been a change then remember that.
                 if set.get() != new_set {
                     changed += 1;
@@ -0,0 +1,300 @@
+use crate::index::Grouping;
+use crate::index_helpers::*;
+use crate::step::{Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+
+pub trait SingleDigit {
+    fn find_skyscraper(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_two_string_kite(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_turbot_fish(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_empty_rectangle(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// A strong link, a unit where the digit can only be in two places.
+#[derive(Debug, Clone, Copy)]
+struct StrongLink {
+    unit: Unit,
+    ends: [usize; 2],
+}
+
+/// The two unsolved positions in unit `g`/`g_idx` that hold `m`,
+/// if there are exactly two and it isn't solved in the unit.
+pub(crate) fn conjugate_pair(
+    sudoku: &Sudoku,
+    g: Grouping,
+    g_idx: usize,
+    m: usize,
+) -> Option<[usize; 2]> {
+    let mut ends = Vec::with_capacity(2);
+    for i in sudoku.house(Unit::new(g, g_idx)) {
+        if sudoku[i].get_candidates() & m != 0 {
+            if sudoku[i].is_solved() {
+                return None;
+            }
+            ends.push(i);
+        }
+    }
+    if ends.len() == 2 {
+        Some([ends[0], ends[1]])
+    } else {
+        None
+    }
+}
+
+fn strong_links(sudoku: &Sudoku, m: usize) -> Vec<StrongLink> {
+    sudoku
+        .houses()
+        .filter_map(|unit| {
+            conjugate_pair(sudoku, unit.grouping, unit.index, m)
+                .map(|ends| StrongLink { unit, ends })
+        })
+        .collect()
+}
+
+/// Name the pattern made by two strong links joined at `b` and `c`.
+fn classify(
+    sudoku: &Sudoku,
+    first: &StrongLink,
+    second: &StrongLink,
+    b: usize,
+    c: usize,
+) -> Technique {
+    let (b_row, b_col, _) = get_index_tuple(b);
+    let (c_row, c_col, _) = get_index_tuple(c);
+    let (b_box, c_box) = (sudoku.box_of(b), sudoku.box_of(c));
+    match (first.unit.grouping, second.unit.grouping) {
+        (Grouping::Row, Grouping::Row) if b_col == c_col => Technique::Skyscraper,
+        (Grouping::Column, Grouping::Column) if b_row == c_row => Technique::Skyscraper,
+        (Grouping::Row, Grouping::Column) | (Grouping::Column, Grouping::Row) if b_box == c_box => {
+            Technique::TwoStringKite
+        }
+        _ => Technique::TurbotFish,
+    }
+}
+
+/// Two strong links A=B and C=D where B sees C. One of A or D has to be
+/// the digit, so anything seeing both of them can't be.
+fn turbot_steps(sudoku: &Sudoku, val_idx: usize) -> Vec<Step> {
+    let m = 1 << val_idx;
+    let links = strong_links(sudoku, m);
+    let mut steps = Vec::new();
+    for (n, first) in links.iter().enumerate() {
+        for second in links[n + 1..].iter() {
+            for (a, b) in [
+                (first.ends[0], first.ends[1]),
+                (first.ends[1], first.ends[0]),
+            ] {
+                for (d, c) in [
+                    (second.ends[0], second.ends[1]),
+                    (second.ends[1], second.ends[0]),
+                ] {
+                    let pattern = [a, b, c, d];
+                    // All four have to be different and only joined at b and c
+                    if (1..4).any(|x| pattern[..x].contains(&pattern[x])) || !sudoku.sees(b, c) {
+                        continue;
+                    }
+                    let eliminations: Vec<Candidate> = sudoku
+                        .common_peer_eliminations(&[a, d], m)
+                        .into_iter()
+                        .filter(|e| !pattern.contains(&e.index))
+                        .collect();
+                    if eliminations.is_empty() {
+                        continue;
+                    }
+                    steps.push(Step {
+                        technique: classify(sudoku, first, second, b, c),
+                        units: vec![first.unit, second.unit],
+                        cells: pattern.to_vec(),
+                        digits: vec![val_idx + 1],
+                        eliminations,
+                        placements: vec![],
+                        links: vec![],
+                        sets: vec![],
+                    });
+                }
+            }
+        }
+    }
+    steps
+}
+
+/// The digit in box `box_i` only lives in one row and one column of the box.
+/// A strong link from that row (or column) lets us remove the digit
+/// where the link's far end meets the box's column (or row).
+fn empty_rectangle_steps(sudoku: &Sudoku, box_i: usize, val_idx: usize) -> Vec<Step> {
+    // This needs the box to be a 3x3 square.
+    if sudoku.jigsaw().is_some() {
+        return vec![];
+    }
+    let m = 1 << val_idx;
+    let box_cells: Vec<usize> = Grouping::Box
+        .iter(box_i)
+        .filter(|i| sudoku[*i].get_candidates() & m != 0)
+        .collect();
+    if box_cells.len() < 2 || box_cells.iter().any(|i| sudoku[*i].is_solved()) {
+        return vec![];
+    }
+    let rows: Vec<usize> = box_cells.iter().map(|i| get_index_tuple(*i).0).collect();
+    let cols: Vec<usize> = box_cells.iter().map(|i| get_index_tuple(*i).1).collect();
+    // If it's all in a single row or column then it's pointing.
+    if rows.iter().all(|r| *r == rows[0]) || cols.iter().all(|c| *c == cols[0]) {
+        return vec![];
+    }
+    let start_row = Grouping::Box.start_row(box_i);
+    let start_col = Grouping::Box.start_column(box_i);
+    let in_band = |r: usize| r >= start_row && r < start_row + 3;
+    let in_stack = |c: usize| c >= start_col && c < start_col + 3;
+
+    let mut steps = Vec::new();
+    for er_row in start_row..start_row + 3 {
+        for er_col in start_col..start_col + 3 {
+            if !rows
+                .iter()
+                .zip(cols.iter())
+                .all(|(r, c)| *r == er_row || *c == er_col)
+            {
+                continue;
+            }
+            // Column links with one end in the box's row
+            // and row links with one end in the box's column.
+            let links = (0..9)
+                .filter(|c| !in_stack(*c))
+                .map(|c| (Grouping::Column, c))
+                .chain((0..9).filter(|r| !in_band(*r)).map(|r| (Grouping::Row, r)));
+            for (g, g_idx) in links {
+                let Some(ends) = conjugate_pair(sudoku, g, g_idx, m) else {
+                    continue;
+                };
+                for (near, far) in [(ends[0], ends[1]), (ends[1], ends[0])] {
+                    let (near_row, near_col, _) = get_index_tuple(near);
+                    let (far_row, far_col, _) = get_index_tuple(far);
+                    let target = match g {
+                        Grouping::Column if near_row == er_row && !in_band(far_row) => {
+                            to_index(far_row, er_col)
+                        }
+                        Grouping::Row if near_col == er_col && !in_stack(far_col) => {
+                            to_index(er_row, far_col)
+                        }
+                        _ => continue,
+                    };
+                    if sudoku[target].is_solved() || sudoku[target].get() & m == 0 {
+                        continue;
+                    }
+                    steps.push(Step {
+                        technique: Technique::EmptyRectangle,
+                        units: vec![
+                            Unit::new(Grouping::Box, box_i),
+                            Unit::new(Grouping::Row, er_row),
+                            Unit::new(Grouping::Column, er_col),
+                            Unit::new(g, g_idx),
+                        ],
+                        cells: box_cells.iter().cloned().chain([near, far]).collect(),
+                        digits: vec![val_idx + 1],
+                        eliminations: vec![Candidate::new(target, val_idx + 1)],
+                        placements: vec![],
+                        links: vec![],
+                        sets: vec![],
+                    });
+                }
+            }
+        }
+    }
+    steps
+}
+
+fn turbot_kind(sudoku: &Sudoku, technique: Technique) -> impl Iterator<Item = Step> + '_ {
+    (0..9)
+        .flat_map(move |val_idx| turbot_steps(sudoku, val_idx))
+        .filter(move |s| s.technique == technique)
+}
+
+impl SingleDigit for Sudoku {
+    fn find_skyscraper(&self) -> impl Iterator<Item = Step> + '_ {
+        turbot_kind(self, Technique::Skyscraper)
+    }
+
+    fn find_two_string_kite(&self) -> impl Iterator<Item = Step> + '_ {
+        turbot_kind(self, Technique::TwoStringKite)
+    }
+
+    fn find_turbot_fish(&self) -> impl Iterator<Item = Step> + '_ {
+        turbot_kind(self, Technique::TurbotFish)
+    }
+
+    fn find_empty_rectangle(&self) -> impl Iterator<Item = Step> + '_ {
+        (0..9).flat_map(move |box_i| {
+            (0..9).flat_map(move |val_idx| empty_rectangle_steps(self, box_i, val_idx))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::HARD;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+    use crate::test_helpers::with_ones;
+
+    #[test]
+    fn test_skyscraper() {
+        let sr = parse_sudoku(HARD).unwrap().try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.skyscraper > 0);
+    }
+
+    #[test]
+    fn test_two_string_kite() {
+        let s = "..3....4.4..2.........9..26....7.....1.9.2...26......85....7.......6.8.33......69";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.is_valid);
+        assert!(sr.two_string_kite > 0);
+    }
+
+    #[test]
+    fn test_turbot_fish() {
+        // Box 1 has r1c1=r2c2, row 2 joins r2c2 to r2c6,
+        // and column 6 has r2c6=r8c6. So r8c1 can't be 1.
+        let p = with_ones(&[
+            to_index(0, 0),
+            to_index(1, 1),
+            to_index(1, 5),
+            to_index(7, 5),
+            to_index(7, 0),
+            to_index(4, 0),
+            to_index(7, 8),
+        ]);
+        let steps: Vec<Step> = p.find_turbot_fish().collect();
+        assert_eq!(1, steps.len());
+        assert_eq!(
+            vec![Unit::new(Grouping::Column, 5), Unit::new(Grouping::Box, 0)],
+            steps[0].units
+        );
+        assert_eq!(
+            vec![Candidate::new(to_index(7, 0), 1)],
+            steps[0].eliminations
+        );
+        assert!(p.find_skyscraper().next().is_none());
+    }
+
+    #[test]
+    fn test_empty_rectangle() {
+        // Box 1 only has 1 in row 1 and column 1 and
+        // column 6 has r1c6=r7c6. So r7c1 can't be 1.
+        let p = with_ones(&[
+            to_index(0, 1),
+            to_index(0, 2),
+            to_index(1, 0),
+            to_index(2, 0),
+            to_index(0, 5),
+            to_index(6, 5),
+            to_index(6, 0),
+            to_index(6, 8),
+        ]);
+        let step = p.find_empty_rectangle().next().unwrap();
+        assert_eq!(Technique::EmptyRectangle, step.technique);
+        assert_eq!(Unit::new(Grouping::Column, 5), step.units[3]);
+        assert_eq!(vec![Candidate::new(to_index(6, 0), 1)], step.eliminations);
+    }
+}
@@ -0,0 +1,295 @@
+/// The shape of the boxes of a grid, see `Sudoku::box_size`.
+///
+/// A box is `rows` high and `cols` wide so the grid has
+/// `rows * cols` rows, columns, boxes, and digits.
+///
+/// http://sudopedia.enjoysudoku.com/Sudoku.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoxSize {
+    rows: usize,
+    cols: usize,
+}
+
+impl BoxSize {
+    /// 4x4 with 2x2 boxes.
+    pub const FOUR: BoxSize = BoxSize { rows: 2, cols: 2 };
+    /// 6x6 with boxes two rows high and three columns wide.
+    pub const SIX: BoxSize = BoxSize { rows: 2, cols: 3 };
+    /// The classic 9x9.
+    pub const NINE: BoxSize = BoxSize { rows: 3, cols: 3 };
+    /// 16x16 hex Sudoku.
+    pub const SIXTEEN: BoxSize = BoxSize { rows: 4, cols: 4 };
+    /// 25x25 with 5x5 boxes.
+    pub const TWENTY_FIVE: BoxSize = BoxSize { rows: 5, cols: 5 };
+
+    /// The boxes of a grid with `digits` digits.
+    ///
+    /// Panics for anything but the sizes above, which are the
+    /// ones with index tables.
+    pub(crate) const fn with_digits(digits: usize) -> BoxSize {
+        match digits {
+            4 => BoxSize::FOUR,
+            6 => BoxSize::SIX,
+            9 => BoxSize::NINE,
+            16 => BoxSize::SIXTEEN,
+            25 => BoxSize::TWENTY_FIVE,
+            _ => panic!("Grids have 4, 6, 9, 16, or 25 digits"),
+        }
+    }
+
+    /// How many rows high a box is.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// How many columns wide a box is.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// How many digits, and how many positions in a house.
+    pub fn digits(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// How many positions in the grid.
+    pub fn positions(&self) -> usize {
+        self.digits() * self.digits()
+    }
+
+    pub fn to_index(&self, row: usize, col: usize) -> usize {
+        row * self.digits() + col
+    }
+
+    pub fn box_of(&self, i: usize) -> usize {
+        let row = i / self.digits();
+        let col = i % self.digits();
+        (row / self.rows) * self.rows + col / self.cols
+    }
+
+    /// The positions of row `idx` from left to right.
+    pub fn row(&self, idx: usize) -> Vec<usize> {
+        (0..self.digits()).map(|n| self.to_index(idx, n)).collect()
+    }
+
+    /// The positions of column `idx` from top to bottom.
+    pub fn column(&self, idx: usize) -> Vec<usize> {
+        (0..self.digits()).map(|n| self.to_index(n, idx)).collect()
+    }
+
+    /// The positions of box `idx`, row by row. Boxes are numbered
+    /// left to right then top to bottom.
+    pub fn boxed(&self, idx: usize) -> Vec<usize> {
+        // There are `rows` boxes across the grid.
+        let start_row = (idx / self.rows) * self.rows;
+        let start_col = (idx % self.rows) * self.cols;
+        (0..self.digits())
+            .map(|n| self.to_index(start_row + n / self.cols, start_col + n % self.cols))
+            .collect()
+    }
+
+    /// Every row, then every column, then every box.
+    pub fn houses(&self) -> Vec<Vec<usize>> {
+        let n = self.digits();
+        let rows = (0..n).map(|idx| self.row(idx));
+        let columns = (0..n).map(|idx| self.column(idx));
+        let boxes = (0..n).map(|idx| self.boxed(idx));
+        rows.chain(columns).chain(boxes).collect()
+    }
+
+    /// The character for digit `d`; 1 through 9 then A onwards.
+    pub(crate) fn symbol(d: usize) -> char {
+        std::char::from_digit(d as u32, 36)
+            .unwrap()
+            .to_ascii_uppercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brute_force::BruteForce;
+    use crate::error::SudokuErr;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+    use crate::sudoku::Sudoku;
+
+    /// A solved grid where each row is the one above shifted along.
+    fn pattern(size: BoxSize) -> String {
+        let (rows, cols) = (size.rows(), size.cols());
+        let n = size.digits();
+        (0..size.positions())
+            .map(|i| {
+                let (r, c) = (i / n, i % n);
+                BoxSize::symbol((cols * (r % rows) + r / rows + c) % n + 1)
+            })
+            .collect()
+    }
+
+    /// `pattern` with every position on a knight's move pattern left out.
+    fn knight_gaps(size: BoxSize) -> String {
+        let n = size.digits();
+        pattern(size)
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if (i / n * 2 + i % n) % 5 < 2 { '.' } else { c })
+            .collect()
+    }
+
+    #[test]
+    fn test_houses() {
+        let six = BoxSize::SIX;
+        assert_eq!(36, six.positions());
+        assert_eq!((2, 3), (six.rows(), six.cols()));
+        assert_eq!(vec![0, 1, 2, 6, 7, 8], six.boxed(0));
+        assert_eq!(vec![27, 28, 29, 33, 34, 35], six.boxed(5));
+        assert_eq!(5, six.box_of(35));
+        assert_eq!(18, six.houses().len());
+        assert_eq!(six, BoxSize::with_digits(6));
+        let nine = BoxSize::NINE;
+        assert_eq!(vec![30, 31, 32, 39, 40, 41, 48, 49, 50], nine.boxed(4));
+        assert_eq!(vec![2, 11, 20, 29, 38, 47, 56, 65, 74], nine.column(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_size() {
+        BoxSize::with_digits(7);
+    }
+
+    #[test]
+    fn test_sized_houses() {
+        let p = parse_sized::<6>(&pattern(BoxSize::SIX)).unwrap();
+        assert_eq!(BoxSize::SIX, p.box_size());
+        assert_eq!(18, p.houses().count());
+        for (u, cells) in p.houses().zip(BoxSize::SIX.houses()) {
+            let mut house = p.house(u).to_vec();
+            house.sort();
+            assert_eq!(cells, house);
+        }
+        assert_eq!(5, p.box_of(35));
+    }
+
+    #[test]
+    fn test_parse_sized() {
+        let p = parse_sized::<4>("1... .... .... ...2").unwrap();
+        assert_eq!(Some(1), p[0].value());
+        assert_eq!(0b1100, p[3].get_candidates());
+        assert_eq!("1..............2", p.oneline());
+        // Too short, a digit that's too big even with enough positions
+        // after it, and a repeated digit.
+        assert!(matches!(parse_sized::<4>("1..."), Err(SudokuErr::Parse())));
+        assert!(matches!(
+            parse_sized::<4>(&format!("5{}", ".".repeat(16))),
+            Err(SudokuErr::Parse())
+        ));
+        assert!(matches!(
+            parse_sized::<4>(&format!("11{}", ".".repeat(14))),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+        let hex = parse_sized::<16>(&pattern(BoxSize::SIXTEEN).to_lowercase()).unwrap();
+        assert!(hex.is_solved() && hex.is_valid());
+        assert_eq!(Some(16), hex[15].value());
+        assert_eq!(pattern(BoxSize::SIXTEEN), hex.oneline());
+        // Hex digits stop at G.
+        assert!(matches!(
+            parse_sized::<16>(&format!("H{}", ".".repeat(255))),
+            Err(SudokuErr::Parse())
+        ));
+        // The same as parse_sudoku for 9x9.
+        let nine = parse_sized::<9>(HARD).unwrap();
+        assert_eq!(parse_sudoku(HARD).unwrap().oneline(), nine.oneline());
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        // 0 to F are the digits 1 to 16.
+        let zero_f: String = knight_gaps(BoxSize::SIXTEEN)
+            .chars()
+            .map(|c| match c.to_digit(36) {
+                Some(d) => std::char::from_digit(d - 1, 16).unwrap(),
+                None => c,
+            })
+            .collect();
+        let p = parse_hex(&zero_f).unwrap();
+        assert_eq!(knight_gaps(BoxSize::SIXTEEN), p.oneline());
+        assert_eq!(zero_f.to_uppercase(), p.hex_oneline());
+        assert_eq!(Some('2'), zero_f.chars().nth(2));
+        assert_eq!(Some(3), p[2].value());
+        let solved = p.brute_force().unwrap();
+        assert_eq!(pattern(BoxSize::SIXTEEN), solved.oneline());
+        // G isn't a hex digit, and there have to be 256 positions.
+        assert!(matches!(
+            parse_hex(&format!("G{}", ".".repeat(255))),
+            Err(SudokuErr::Parse())
+        ));
+        assert!(matches!(parse_hex(&zero_f[1..]), Err(SudokuErr::Parse())));
+        assert!(matches!(
+            parse_hex(&format!("00{}", ".".repeat(254))),
+            Err(SudokuErr::InvalidPuzzle())
+        ));
+    }
+
+    #[test]
+    fn test_count_four() {
+        let empty = parse_sized::<4>(&".".repeat(16)).unwrap();
+        assert_eq!(288, empty.count_solutions(1000));
+    }
+
+    #[test]
+    fn test_solve_six() {
+        let pzl = "...21. .....5 2..... 3....4 ..1... ..46..";
+        let mut p = parse_sized::<6>(pzl).unwrap();
+        assert!(p.has_unique_solution());
+        let solution = p.brute_force().unwrap();
+        assert!(p.solve_singles());
+        assert_eq!(solution.oneline(), p.oneline());
+        assert!(p.is_solved() && p.is_valid());
+        assert_eq!(
+            "\
+453 216
+162 345
+
+245 163
+316 524
+
+631 452
+524 631
+",
+            p.to_string()
+        );
+    }
+
+    #[test]
+    fn test_solve_sixteen() {
+        let size = BoxSize::SIXTEEN;
+        let p: Sudoku<16> = parse_sized(&knight_gaps(size)).unwrap();
+        let solution = p.brute_force().unwrap();
+        assert!(solution.is_solved() && solution.is_valid());
+        for i in 0..size.positions() {
+            if let Some(d) = p[i].value() {
+                assert_eq!(Some(d), solution[i].value());
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_twenty_five() {
+        let size = BoxSize::TWENTY_FIVE;
+        let pzl: String = pattern(size)
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 3 == 0 { '.' } else { c })
+            .collect();
+        let p = parse_sized::<25>(&pzl).unwrap();
+        assert_eq!(Some(pattern(size)), p.brute_force().map(|s| s.oneline()));
+    }
+
+    #[test]
+    fn test_nine_is_sudoku() {
+        let p: Sudoku = parse_sized(HARD).unwrap();
+        assert_eq!(BoxSize::NINE, p.box_size());
+        assert_eq!(p.clone().try_solve().state, p.brute_force().unwrap().oneline());
+    }
+}
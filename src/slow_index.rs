@@ -84,19 +84,20 @@ impl Iterator for RelatedIndexIterator {
 }
 
 pub struct MultiRelatedIndexIterator {
-    pub idx: [usize; 4],
+    pub idx: [usize; 8],
     pub num_idx: usize,
     pub gp: Box<dyn GenPosition>,
 }
 impl MultiRelatedIndexIterator {
     pub fn new(num_idx: usize) -> Self {
-        assert!(num_idx <= 4);
-        assert!(num_idx >= 2);
-        let mut idx = [0; 4];
+        assert!(num_idx <= 8);
+        assert!(num_idx >= 1);
+        let mut idx = [0; 8];
         for (i, idx_item) in idx.iter_mut().enumerate().take(num_idx) {
             *idx_item = i;
         }
-        idx[num_idx - 1] -= 1;
+        // This wraps for a single index; next() wraps it back to 0.
+        idx[num_idx - 1] = idx[num_idx - 1].wrapping_sub(1);
         MultiRelatedIndexIterator {
             num_idx,
             idx,
@@ -116,9 +117,9 @@ impl Iterator for MultiRelatedIndexIterator {
 
         // While there are still levels to visit
         // keep trying to increment.
-        while current_level < 4 {
+        while current_level < self.num_idx {
             // Move the current level forward one unconditionally
-            self.idx[current_level] += 1;
+            self.idx[current_level] = self.idx[current_level].wrapping_add(1);
 
             // Calculate how many positions/numbers are needed
             // to fill out the remaining levels.
@@ -265,6 +266,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pair_triple_iter() {
+        // 9 choose 2 = 36
+        let si =
+            MultiRelatedIndexIterator::new(2).set_gen_position(Box::new(RowGenPosition::new(0)));
+        assert_eq!(36, si.count());
+        // 9 choose 3 = 84
+        let si =
+            MultiRelatedIndexIterator::new(3).set_gen_position(Box::new(RowGenPosition::new(0)));
+        assert_eq!(84, si.count());
+    }
+
+    #[test]
+    fn test_single_and_eight_iter() {
+        // 9 choose 1 = 9 choose 8 = 9
+        for sz in [1, 8] {
+            let si = MultiRelatedIndexIterator::new(sz)
+                .set_gen_position(Box::new(RowGenPosition::new(0)));
+            assert_eq!(9, si.count());
+        }
+    }
+
     #[test]
     fn test_box_quad_iter() {
         for i in 0..9 {
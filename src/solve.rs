@@ -1,9 +1,22 @@
+use crate::als::AlmostLockedSets;
 use crate::box_line::LineBoxReduction;
+use crate::brute_force::BruteForce;
+use crate::chain::Chains;
+use crate::coloring::Coloring;
+use crate::finned_fish::FinnedFish;
+use crate::fish::Fish;
 use crate::hidden_singles::HiddenSingles;
+use crate::killer::KillerCages;
+use crate::naked_singles::NakedSingles;
 use crate::pointing_pairs::Pointing;
 use crate::remove_candidates::RemoveCandidates;
+use crate::single_digit::SingleDigit;
+use crate::step::{Step, Technique, Unit};
 use crate::subset::FindSubset;
 use crate::sudoku::Sudoku;
+use crate::uniqueness::Uniqueness;
+use crate::wing::Wing;
+use std::collections::BTreeMap;
 
 #[derive(Debug)]
 pub struct SolveReport {
@@ -27,22 +40,228 @@ pub struct SolveReport {
     pub naked_singles: usize,
     pub num_pointing: usize,
     pub box_line: usize,
+    /// The number of candidates removed because no combination
+    /// of a Killer cage could use them.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+    pub cage_combination: usize,
+    /// The number of candidates removed by Killer innies and outies.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+    pub innies_outies: usize,
     /// The number of times that a {Naked,Hidden}{Double,Triple} is
     /// used to remove a candidate.
     ///
     /// http://sudopedia.enjoysudoku.com/Solving_Technique.html#Subsets
     pub subsets_used: usize,
+    /// The number of candidates removed by an X-Wing, Swordfish, or Jellyfish.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Fish.html
+    pub fish: usize,
+    /// The number of candidates removed by a Skyscraper.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Skyscraper.html
+    pub skyscraper: usize,
+    /// The number of candidates removed by a 2-String Kite.
+    ///
+    /// http://sudopedia.enjoysudoku.com/2-String_Kite.html
+    pub two_string_kite: usize,
+    /// The number of candidates removed by a Turbot Fish.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Turbot_Fish.html
+    pub turbot_fish: usize,
+    /// The number of candidates removed by an Empty Rectangle.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Empty_Rectangle.html
+    pub empty_rectangle: usize,
+    /// The number of candidates removed by a Finned or Sashimi fish.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Finned_X-Wing.html
+    pub finned_fish: usize,
+    /// The number of candidates removed by an XY-Wing.
+    ///
+    /// http://sudopedia.enjoysudoku.com/XY-Wing.html
+    pub xy_wing: usize,
+    /// The number of candidates removed by an XYZ-Wing.
+    ///
+    /// http://sudopedia.enjoysudoku.com/XYZ-Wing.html
+    pub xyz_wing: usize,
+    /// The number of candidates removed by a Color Trap or Color Wrap.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Simple_Colors.html
+    pub simple_coloring: usize,
+    /// The number of candidates removed by Multi-Coloring.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Multi-Colors.html
+    pub multi_coloring: usize,
+    /// The number of candidates removed by a Unique Rectangle or
+    /// Hidden Unique Rectangle. Always 0 unless `uniqueness` is set.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Uniqueness_Test.html
+    pub unique_rectangle: usize,
+    /// The number of positions solved by BUG+1.
+    /// Always 0 unless `uniqueness` is set.
+    ///
+    /// http://sudopedia.enjoysudoku.com/BUG.html
+    pub bug_plus_one: usize,
+    /// The number of candidates removed by an X-Chain.
+    ///
+    /// http://sudopedia.enjoysudoku.com/X-Chain.html
+    pub x_chain: usize,
+    /// The number of candidates removed by an XY-Chain.
+    ///
+    /// http://sudopedia.enjoysudoku.com/XY-Chain.html
+    pub xy_chain: usize,
+    /// The number of candidates removed by an Alternating Inference Chain.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Alternating_Inference_Chain.html
+    pub aic: usize,
+    /// The number of candidates removed by an ALS-XZ.
+    ///
+    /// http://sudopedia.enjoysudoku.com/ALS-XZ.html
+    pub als_xz: usize,
+    /// The number of candidates removed by an ALS-XY-Wing.
+    ///
+    /// http://sudopedia.enjoysudoku.com/ALS-XY-Wing.html
+    pub als_xy_wing: usize,
+    /// The number of candidates removed by an ALS Chain.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Almost_Locked_Set.html
+    pub als_chain: usize,
+    /// The same counts keyed by each technique that was used.
+    pub techniques: BTreeMap<Technique, usize>,
+    /// Did the logical techniques stall and leave the rest of the
+    /// puzzle to brute force. Only possible if `brute_force` is set.
+    pub guessed: bool,
     /// The string representation of how the board looks
     ///  after trying to solve the puzzle
     pub state: String,
 }
 
+impl SolveReport {
+    /// Add `count` to the counter for `technique`.
+    fn record(&mut self, technique: Technique, count: usize) {
+        *self.techniques.entry(technique).or_insert(0) += count;
+        let counter = match technique {
+            Technique::HiddenSingle => &mut self.hidden_singles,
+            Technique::NakedSingle => &mut self.naked_singles,
+            Technique::Pointing => &mut self.num_pointing,
+            Technique::BoxLine => &mut self.box_line,
+            Technique::CageCombination => &mut self.cage_combination,
+            Technique::Innies | Technique::Outies => &mut self.innies_outies,
+            Technique::NakedSubset(_) | Technique::HiddenSubset(_) => &mut self.subsets_used,
+            Technique::Fish(_) => &mut self.fish,
+            Technique::FinnedFish(_) | Technique::SashimiFish(_) => &mut self.finned_fish,
+            Technique::Skyscraper => &mut self.skyscraper,
+            Technique::TwoStringKite => &mut self.two_string_kite,
+            Technique::TurbotFish => &mut self.turbot_fish,
+            Technique::EmptyRectangle => &mut self.empty_rectangle,
+            Technique::XYWing => &mut self.xy_wing,
+            Technique::XYZWing => &mut self.xyz_wing,
+            Technique::UniqueRectangle(_) | Technique::HiddenUniqueRectangle => {
+                &mut self.unique_rectangle
+            }
+            Technique::BugPlusOne => &mut self.bug_plus_one,
+            Technique::ColorTrap | Technique::ColorWrap => &mut self.simple_coloring,
+            Technique::MultiColoring => &mut self.multi_coloring,
+            Technique::XChain => &mut self.x_chain,
+            Technique::XYChain => &mut self.xy_chain,
+            Technique::AlternatingInferenceChain => &mut self.aic,
+            Technique::AlsXZ => &mut self.als_xz,
+            Technique::AlsXYWing => &mut self.als_xy_wing,
+            Technique::AlsChain => &mut self.als_chain,
+        };
+        *counter += count;
+    }
+}
+
+/// Options that change which techniques the solver can use.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolveOptions {
+    /// Allow techniques that assume the puzzle has a single solution,
+    /// Unique Rectangles and BUG+1. These can make wrong deductions
+    /// on puzzles with more than one solution so they are off by default.
+    pub uniqueness: bool,
+    /// If the techniques stall then finish the puzzle by brute force.
+    /// The report's `guessed` says if that happened.
+    pub brute_force: bool,
+}
+
 pub trait Solveable {
     fn try_solve(self) -> SolveReport;
+    fn try_solve_with(self, options: SolveOptions) -> SolveReport;
+}
+
+/// Apply all of the steps returning the total (changed, solved).
+///
+/// Placements aren't propagated to peers; that's left
+/// to the remove_candidates at the top of the solve loop.
+fn apply_all(sudoku: &mut Sudoku, steps: Vec<Step>) -> (usize, usize) {
+    steps
+        .iter()
+        .map(|s| s.apply_exact(sudoku))
+        .fold((0, 0), |(c, s), (c1, s1)| (c + c1, s + s1))
+}
+
+/// Find the next deduction for the puzzle without changing it.
+///
+/// Techniques are tried from easiest to hardest so the returned
+/// step is the simplest one available. Returns None if the puzzle
+/// is solved or none of the techniques can make progress.
+///
+/// The candidates are expected to be up to date; `parse_sudoku`
+/// and `Step::apply` both keep them that way.
+pub fn next_step(sudoku: &Sudoku) -> Option<Step> {
+    next_step_with(sudoku, SolveOptions::default())
+}
+
+/// Like `next_step` but only using the techniques allowed by `options`.
+pub fn next_step_with(sudoku: &Sudoku, options: SolveOptions) -> Option<Step> {
+    sudoku
+        .find_hidden()
+        .next()
+        .or_else(|| sudoku.find_naked().next())
+        .or_else(|| sudoku.find_pointing().next())
+        .or_else(|| sudoku.find_box_line().next())
+        .or_else(|| sudoku.find_cage_combination().next())
+        .or_else(|| sudoku.find_innies_outies().next())
+        .or_else(|| sudoku.find_subset().next())
+        .or_else(|| sudoku.find_fish().next())
+        .or_else(|| sudoku.find_skyscraper().next())
+        .or_else(|| sudoku.find_two_string_kite().next())
+        .or_else(|| sudoku.find_turbot_fish().next())
+        .or_else(|| sudoku.find_empty_rectangle().next())
+        .or_else(|| sudoku.find_finned_fish().next())
+        .or_else(|| sudoku.find_xy_wing().next())
+        .or_else(|| sudoku.find_xyz_wing().next())
+        .or_else(|| {
+            options
+                .uniqueness
+                .then(|| sudoku.find_unique_rectangle().next())
+                .flatten()
+        })
+        .or_else(|| {
+            options
+                .uniqueness
+                .then(|| sudoku.find_bug_plus_one().next())
+                .flatten()
+        })
+        .or_else(|| sudoku.find_simple_coloring().next())
+        .or_else(|| sudoku.find_multi_coloring().next())
+        .or_else(|| sudoku.find_x_chain().next())
+        .or_else(|| sudoku.find_xy_chain().next())
+        .or_else(|| sudoku.find_aic().next())
+        .or_else(|| sudoku.find_als_xz().next())
+        .or_else(|| sudoku.find_als_xy_wing().next())
+        .or_else(|| sudoku.find_als_chain().next())
 }
 
 impl Solveable for Sudoku {
-    fn try_solve(mut self) -> SolveReport {
+    fn try_solve(self) -> SolveReport {
+        self.try_solve_with(SolveOptions::default())
+    }
+
+    fn try_solve_with(mut self, options: SolveOptions) -> SolveReport {
         let mut sr = SolveReport {
             is_solved: false,
             is_valid: false,
@@ -51,48 +270,74 @@ impl Solveable for Sudoku {
             hidden_singles: 0,
             naked_singles: 0,
             subsets_used: 0,
+            fish: 0,
+            skyscraper: 0,
+            two_string_kite: 0,
+            turbot_fish: 0,
+            empty_rectangle: 0,
+            finned_fish: 0,
+            xy_wing: 0,
+            xyz_wing: 0,
+            simple_coloring: 0,
+            multi_coloring: 0,
+            unique_rectangle: 0,
+            bug_plus_one: 0,
+            x_chain: 0,
+            xy_chain: 0,
+            aic: 0,
+            als_xz: 0,
+            als_xy_wing: 0,
+            als_chain: 0,
             num_pointing: 0,
             box_line: 0,
+            cage_combination: 0,
+            innies_outies: 0,
+            guessed: false,
+            techniques: BTreeMap::new(),
             state: String::new(),
         };
-        // Remember how many were solved with naked singles.
-        let mut cont = true;
-        while cont && !self.is_solved() {
-            // Then remove everything that can't be a candidate anymore.
-            let (c_0, _s_0) = self.remove_candidates(false);
-            // Try and assign hidden singles
-            let hs = self.find_hidden();
-            if hs > 0 {
-                sr.hidden_singles += hs;
-                continue;
-            }
-            // Remove things setting the solved bit along the way.
-            let (c_1, s_1) = self.remove_candidates(true);
-            if s_1 > 0 {
-                sr.naked_singles += s_1;
-                continue;
+        while !self.is_solved() {
+            // Remove everything that can't be a candidate anymore.
+            self.remove_candidates(false);
+            // Try and assign hidden singles one
+            // row, col, box, or region at a time.
+            let mut hs = 0;
+            let houses: Vec<Unit> = self.houses().collect();
+            for u in houses {
+                let hidden = self.find_hidden_in(u.grouping, u.index);
+                hs += apply_all(&mut self, hidden).1;
             }
-
-            let (c_2, _s_2) = self.handle_pointing();
-            if c_2 > 0 {
-                sr.num_pointing += c_2;
+            if hs > 0 {
+                sr.record(Technique::HiddenSingle, hs);
                 continue;
             }
-
-            let (c_3, _s_4) = self.box_line_reduce();
-            if c_3 > 0 {
-                sr.box_line += c_3;
+            // Then everything that only has one option left.
+            let naked = self.find_naked().collect();
+            let (_, ns) = apply_all(&mut self, naked);
+            if ns > 0 {
+                sr.record(Technique::NakedSingle, ns);
                 continue;
             }
 
-            let (c_4, _s_4) = self.find_subset();
-            if c_4 > 0 {
-                sr.subsets_used += c_4;
+            // Everything else in the same order as `next_step`.
+            if let Some(step) = next_step_with(&self, options) {
+                let (changed, solved) = step.apply(&mut self);
+                // BUG+1 places a digit, the rest remove candidates.
+                let count = if step.placements.is_empty() {
+                    changed
+                } else {
+                    solved
+                };
+                sr.record(step.technique, count);
                 continue;
             }
             // We have no more to do because nothing changed.
-            if c_0 == 0 && c_1 == 0 {
-                cont = false;
+            break;
+        }
+        if options.brute_force && !self.is_solved() {
+            if let Some(solution) = self.brute_force() {
+                self = solution;
+                sr.guessed = true;
             }
         }
         // Copy the final state into the report.
@@ -107,6 +352,7 @@ impl Solveable for Sudoku {
 mod tests {
     use super::*;
     use crate::examples::*;
+    use crate::index::Grouping;
     use crate::parse::*;
 
     #[test]
@@ -200,6 +446,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_solve_jigsaw() {
+        let p = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        assert!(p.has_unique_solution());
+        let sr = p.try_solve();
+        assert!(sr.is_solved && !sr.guessed);
+        assert!(sr.num_pointing > 0);
+        assert!(sr.box_line > 0);
+        assert_eq!(
+            "469528137874631952351947268726813549513289674942756381297465813185372496638194725",
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_next_step_jigsaw() {
+        let mut p = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        let mut irregular = 0;
+        while let Some(step) = next_step(&p) {
+            if matches!(step.technique, Technique::Pointing | Technique::BoxLine) {
+                let box_i = step.units.iter().find(|u| u.grouping == Grouping::Box);
+                let box_i = box_i.unwrap().index;
+                // Every elimination is in the box or the line,
+                // going by the jigsaw's boxes.
+                let line = step.units.iter().find(|u| u.grouping != Grouping::Box);
+                let line = p.house(*line.unwrap());
+                for c in step.eliminations.iter() {
+                    assert!(p.box_of(c.index) == box_i || line.contains(&c.index));
+                }
+                // The usual 3x3 box would be somewhere else.
+                if step
+                    .cells
+                    .iter()
+                    .any(|i| p.box_of(*i) != i / 27 * 3 + i % 9 / 3)
+                {
+                    irregular += 1;
+                }
+            }
+            step.apply(&mut p);
+        }
+        assert!(irregular > 0);
+        assert!(p.is_solved() && p.is_valid());
+    }
+
     #[test]
     fn test_try_solve_lots() {
         let c = PUZZLES
@@ -213,6 +503,89 @@ mod tests {
             })
             .filter(|sr| sr.is_solved)
             .count();
-        assert_eq!(12, c)
+        assert_eq!(20, c)
+    }
+
+    #[test]
+    fn test_next_step_easy() {
+        let mut p = parse_sudoku(ONE_LINE).unwrap();
+        let first = next_step(&p).unwrap();
+        assert_eq!(Technique::HiddenSingle, first.technique);
+        assert_eq!(1, first.placements.len());
+        while let Some(step) = next_step(&p) {
+            let (c, s) = step.apply(&mut p);
+            assert!(c + s > 0);
+        }
+        assert!(p.is_solved());
+        assert!(p.is_valid());
+        assert_eq!(
+            "819637425527841369643529178476218953135796284298354716351962847764183592982475631",
+            p.oneline()
+        );
+    }
+
+    #[test]
+    fn test_next_step_pointing() {
+        let s = "010903600000080000900000507002010430000402000064070200701000005000030000005601020";
+        let mut p = parse_sudoku(s).unwrap();
+        let mut pointing = 0;
+        while let Some(step) = next_step(&p) {
+            if step.technique == Technique::Pointing {
+                pointing += 1;
+                assert_eq!(Grouping::Box, step.units[0].grouping);
+                assert!(!step.eliminations.is_empty());
+            }
+            step.apply(&mut p);
+        }
+        assert!(pointing > 0);
+        assert!(p.is_valid());
+    }
+
+    #[test]
+    fn test_next_step_lots() {
+        let c = PUZZLES
+            .lines()
+            .filter_map(|x| parse_sudoku(x).ok())
+            .map(|mut p| {
+                while let Some(step) = next_step(&p) {
+                    step.apply(&mut p);
+                }
+                p
+            })
+            .filter(|p| p.is_solved())
+            .count();
+        assert_eq!(20, c)
+    }
+
+    #[test]
+    fn test_brute_force_fallback() {
+        let s = "9.....1.6..85.6.....4.9.....3.............7.98..7.2.5.71............34..4...68..2";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert!(!sr.is_solved);
+        assert!(!sr.guessed);
+
+        let options = SolveOptions {
+            brute_force: true,
+            ..Default::default()
+        };
+        let sr = parse_sudoku(s).unwrap().try_solve_with(options);
+        assert!(sr.is_solved);
+        assert!(sr.is_valid);
+        assert!(sr.guessed);
+        assert_eq!(
+            parse_sudoku(s).unwrap().brute_force().unwrap().oneline(),
+            sr.state
+        );
+    }
+
+    #[test]
+    fn test_no_guessing_when_logic_is_enough() {
+        let options = SolveOptions {
+            brute_force: true,
+            ..Default::default()
+        };
+        let sr = parse_sudoku(ONE_LINE).unwrap().try_solve_with(options);
+        assert!(sr.is_solved);
+        assert!(!sr.guessed);
     }
 }
@@ -0,0 +1,423 @@
+use crate::candidate_set::{CandidateSet, CandidateSetIterator};
+use crate::index::Grouping;
+use crate::index_helpers::get_index_tuple;
+use crate::remove_mask::RemoveMask;
+use crate::sudoku::Sudoku;
+use std::fmt;
+
+/// The solving technique that was used to make a deduction.
+///
+/// Ordered easiest first within each `Difficulty`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    /// http://sudopedia.enjoysudoku.com/Hidden_Single.html
+    HiddenSingle,
+    /// http://sudopedia.enjoysudoku.com/Naked_Single.html
+    NakedSingle,
+    /// http://sudopedia.enjoysudoku.com/Locked_Candidates.html
+    ///
+    /// A digit in a box is confined to one row or column,
+    /// so it can be removed from the rest of that line.
+    Pointing,
+    /// A digit in a row or column is confined to one box,
+    /// so it can be removed from the rest of that box.
+    BoxLine,
+    /// A Naked Pair, Triple or Quad. The value is the size of the subset.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Naked_Subset.html
+    NakedSubset(usize),
+    /// A Hidden Pair, Triple or Quad. The value is the size of the subset.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Hidden_Subset.html
+    HiddenSubset(usize),
+    /// X-Wing, Swordfish, or Jellyfish. The value is the number of base lines.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Fish.html
+    Fish(usize),
+    /// A fish with extra candidates, the fins, in a single box.
+    /// The value is the number of base lines.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Finned_X-Wing.html
+    FinnedFish(usize),
+    /// A finned fish that would be degenerate without its fins.
+    /// The value is the number of base lines.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Sashimi_X-Wing.html
+    SashimiFish(usize),
+    /// http://sudopedia.enjoysudoku.com/Skyscraper.html
+    Skyscraper,
+    /// http://sudopedia.enjoysudoku.com/2-String_Kite.html
+    TwoStringKite,
+    /// http://sudopedia.enjoysudoku.com/Turbot_Fish.html
+    TurbotFish,
+    /// http://sudopedia.enjoysudoku.com/Empty_Rectangle.html
+    EmptyRectangle,
+    /// http://sudopedia.enjoysudoku.com/XY-Wing.html
+    XYWing,
+    /// http://sudopedia.enjoysudoku.com/XYZ-Wing.html
+    XYZWing,
+    /// Simple coloring where a position sees both colors of a cluster.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Simple_Colors.html
+    ColorTrap,
+    /// Simple coloring where two positions of the same color see each other.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Simple_Colors.html
+    ColorWrap,
+    /// http://sudopedia.enjoysudoku.com/Multi-Colors.html
+    MultiColoring,
+    /// A Unique Rectangle. The value is the type, 1 through 6.
+    /// Only valid for puzzles with a single solution.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Uniqueness_Test.html
+    UniqueRectangle(usize),
+    /// A Unique Rectangle found through strong links instead
+    /// of extra candidates.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Hidden_Unique_Rectangle.html
+    HiddenUniqueRectangle,
+    /// Bivalue Universal Grave + 1.
+    /// Only valid for puzzles with a single solution.
+    ///
+    /// http://sudopedia.enjoysudoku.com/BUG.html
+    BugPlusOne,
+    /// A chain of strong and weak links on a single digit.
+    ///
+    /// http://sudopedia.enjoysudoku.com/X-Chain.html
+    XChain,
+    /// A chain of bi-value cells.
+    ///
+    /// http://sudopedia.enjoysudoku.com/XY-Chain.html
+    XYChain,
+    /// A chain of strong and weak links mixing digits, cells,
+    /// and grouped nodes.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Alternating_Inference_Chain.html
+    AlternatingInferenceChain,
+    /// Two Almost Locked Sets joined by a restricted common candidate,
+    /// or by two for a doubly linked ALS-XZ.
+    ///
+    /// http://sudopedia.enjoysudoku.com/ALS-XZ.html
+    AlsXZ,
+    /// Two Almost Locked Sets each joined to a third.
+    ///
+    /// http://sudopedia.enjoysudoku.com/ALS-XY-Wing.html
+    AlsXYWing,
+    /// Four or more Almost Locked Sets joined one after the other.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Almost_Locked_Set.html
+    AlsChain,
+    /// Candidates a Killer cage can't use in any combination of
+    /// different digits that adds up to its sum.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+    CageCombination,
+    /// The cells of a house outside the cages inside it add
+    /// up to 45 less the cages.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+    Innies,
+    /// The cells sticking out of the cages that cover a house
+    /// add up to the cages less 45.
+    ///
+    /// http://sudopedia.enjoysudoku.com/Killer_Sudoku.html
+    Outies,
+}
+
+/// A single row, column, box, or extra region of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unit {
+    pub grouping: Grouping,
+    /// Which row, column, box, or region; starting at 0.
+    pub index: usize,
+}
+
+/// A single digit in a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    /// The cell index; 0 through 80.
+    pub index: usize,
+    /// The digit; 1 through 9.
+    pub digit: usize,
+}
+
+/// One end of a link. Usually a single candidate, but the cells of a
+/// box that are also in one row or column can act as a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// The cell indexes; more than one for a grouped node.
+    pub cells: Vec<usize>,
+    /// The digit; 1 through 9.
+    pub digit: usize,
+}
+
+/// Two nodes joined together in a chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub from: Node,
+    pub to: Node,
+    /// Strong links mean at least one end is true.
+    /// Weak links mean at most one end is true.
+    pub strong: bool,
+}
+
+/// One deduction made while solving a puzzle, along with
+/// everything needed to explain it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    /// The rows, columns, and boxes that make up the pattern.
+    pub units: Vec<Unit>,
+    /// The cells that make up the pattern.
+    pub cells: Vec<usize>,
+    /// The digits that make up the pattern.
+    pub digits: Vec<usize>,
+    /// Candidates that can be removed.
+    pub eliminations: Vec<Candidate>,
+    /// Candidates that are the solution for their cell.
+    pub placements: Vec<Candidate>,
+    /// For chain and coloring based techniques the
+    /// links that join the pattern together.
+    pub links: Vec<Link>,
+    /// For Almost Locked Set techniques the cells of each set.
+    pub sets: Vec<Vec<usize>>,
+}
+
+impl Unit {
+    pub fn new(grouping: Grouping, index: usize) -> Self {
+        Unit { grouping, index }
+    }
+}
+
+impl Candidate {
+    pub fn new(index: usize, digit: usize) -> Self {
+        Candidate { index, digit }
+    }
+
+    /// Every candidate for the cell at `index` that is in `mask`.
+    pub(crate) fn from_mask(index: usize, mask: usize) -> impl Iterator<Item = Candidate> {
+        CandidateSetIterator::new(mask).map(move |m| Candidate::new(index, to_digit(m)))
+    }
+
+    pub fn mask(self) -> usize {
+        1 << (self.digit - 1)
+    }
+}
+
+/// Convert a single candidate mask into the digit it represents.
+pub(crate) fn to_digit(m: usize) -> usize {
+    m.trailing_zeros() as usize + 1
+}
+
+/// Convert a candidate mask into the digits it contains.
+pub(crate) fn to_digits(m: usize) -> Vec<usize> {
+    CandidateSetIterator::new(m).map(to_digit).collect()
+}
+
+impl From<Candidate> for Node {
+    fn from(c: Candidate) -> Self {
+        Node {
+            cells: vec![c.index],
+            digit: c.digit,
+        }
+    }
+}
+
+impl Link {
+    pub fn new(from: impl Into<Node>, to: impl Into<Node>, strong: bool) -> Self {
+        Link {
+            from: from.into(),
+            to: to.into(),
+            strong,
+        }
+    }
+}
+
+impl Step {
+    /// Apply the step to the puzzle returning (changed, solved).
+    ///
+    /// Placing a digit also removes that digit from
+    /// the candidates of every unsolved peer.
+    pub fn apply(&self, sudoku: &mut Sudoku) -> (usize, usize) {
+        let res = self.apply_exact(sudoku);
+        for c in self.placements.iter() {
+            let unsolved: Vec<usize> = sudoku
+                .peers(c.index)
+                .filter(|p| !sudoku[*p].is_solved())
+                .collect();
+            sudoku.remove_mask(c.mask(), unsolved.into_iter());
+        }
+        res
+    }
+
+    /// Apply only the eliminations and placements named in the step
+    /// returning (changed, solved). Peers are left for remove_candidates.
+    pub(crate) fn apply_exact(&self, sudoku: &mut Sudoku) -> (usize, usize) {
+        let mut masks = [0; 81];
+        for c in self.eliminations.iter() {
+            masks[c.index] |= c.mask();
+        }
+        let changed = masks
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| **m != 0)
+            .map(|(idx, m)| sudoku.remove_mask(*m, std::iter::once(idx)))
+            .sum();
+        let mut solved = 0;
+        for c in self.placements.iter() {
+            if !sudoku[c.index].is_solved() {
+                sudoku[c.index] = CandidateSet::new(c.mask());
+                sudoku[c.index].set_solved();
+                solved += 1;
+            }
+        }
+        (changed, solved)
+    }
+}
+
+fn fish_name(size: usize) -> &'static str {
+    match size {
+        2 => "X-Wing",
+        3 => "Swordfish",
+        4 => "Jellyfish",
+        _ => "Fish",
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let subset_name = |size: usize| match size {
+            2 => "Pair",
+            3 => "Triple",
+            4 => "Quad",
+            _ => "Subset",
+        };
+        match *self {
+            Technique::HiddenSingle => write!(f, "Hidden Single"),
+            Technique::NakedSingle => write!(f, "Naked Single"),
+            Technique::Pointing => write!(f, "Pointing"),
+            Technique::BoxLine => write!(f, "Box/Line Reduction"),
+            Technique::NakedSubset(s) => write!(f, "Naked {}", subset_name(s)),
+            Technique::HiddenSubset(s) => write!(f, "Hidden {}", subset_name(s)),
+            Technique::Fish(s) => write!(f, "{}", fish_name(s)),
+            Technique::FinnedFish(s) => write!(f, "Finned {}", fish_name(s)),
+            Technique::SashimiFish(s) => write!(f, "Sashimi {}", fish_name(s)),
+            Technique::Skyscraper => write!(f, "Skyscraper"),
+            Technique::TwoStringKite => write!(f, "2-String Kite"),
+            Technique::TurbotFish => write!(f, "Turbot Fish"),
+            Technique::EmptyRectangle => write!(f, "Empty Rectangle"),
+            Technique::XYWing => write!(f, "XY-Wing"),
+            Technique::XYZWing => write!(f, "XYZ-Wing"),
+            Technique::ColorTrap => write!(f, "Color Trap"),
+            Technique::ColorWrap => write!(f, "Color Wrap"),
+            Technique::MultiColoring => write!(f, "Multi-Coloring"),
+            Technique::UniqueRectangle(t) => write!(f, "Unique Rectangle Type {}", t),
+            Technique::HiddenUniqueRectangle => write!(f, "Hidden Unique Rectangle"),
+            Technique::BugPlusOne => write!(f, "BUG+1"),
+            Technique::XChain => write!(f, "X-Chain"),
+            Technique::XYChain => write!(f, "XY-Chain"),
+            Technique::AlternatingInferenceChain => write!(f, "AIC"),
+            Technique::AlsXZ => write!(f, "ALS-XZ"),
+            Technique::AlsXYWing => write!(f, "ALS-XY-Wing"),
+            Technique::AlsChain => write!(f, "ALS Chain"),
+            Technique::CageCombination => write!(f, "Cage Combination"),
+            Technique::Innies => write!(f, "Innies"),
+            Technique::Outies => write!(f, "Outies"),
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self.grouping {
+            Grouping::Row => "row",
+            Grouping::Column => "column",
+            Grouping::Box => "box",
+            Grouping::Region => "region",
+        };
+        write!(f, "{} {}", name, self.index + 1)
+    }
+}
+
+/// Write a cell in the r1c1 notation.
+fn write_cell(f: &mut fmt::Formatter, idx: usize) -> fmt::Result {
+    let (row, col, _) = get_index_tuple(idx);
+    write!(f, "r{}c{}", row + 1, col + 1)
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<usize> = self.cells.iter().map(|i| get_index_tuple(*i).0).collect();
+        let cols: Vec<usize> = self.cells.iter().map(|i| get_index_tuple(*i).1).collect();
+        write!(f, "({})", self.digit)?;
+        // Groups are written with all the columns of a row, r1c12,
+        // or all the rows of a column, r12c1.
+        if rows.iter().all(|r| *r == rows[0]) {
+            write!(f, "r{}c", rows[0] + 1)?;
+            cols.iter().try_for_each(|c| write!(f, "{}", c + 1))
+        } else {
+            write!(f, "r")?;
+            rows.iter().try_for_each(|r| write!(f, "{}", r + 1))?;
+            write!(f, "c{}", cols[0] + 1)
+        }
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sep = if self.strong { "=" } else { "-" };
+        write!(f, "{}{}{}", self.from, sep, self.to)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.technique)?;
+        for (i, u) in self.units.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " in " } else { ", " }, u)?;
+        }
+        write!(f, ":")?;
+        for c in self.placements.iter() {
+            write!(f, " ")?;
+            write_cell(f, c.index)?;
+            write!(f, "={}", c.digit)?;
+        }
+        for c in self.eliminations.iter() {
+            write!(f, " ")?;
+            write_cell(f, c.index)?;
+            write!(f, "<>{}", c.digit)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::ONE_LINE;
+    use crate::parse::*;
+
+    #[test]
+    fn test_apply_placement() {
+        let mut p = parse_sudoku(ONE_LINE).unwrap();
+        let step = Step {
+            technique: Technique::NakedSingle,
+            units: vec![],
+            cells: vec![0],
+            digits: vec![8],
+            eliminations: vec![],
+            placements: vec![Candidate::new(0, 8)],
+            links: vec![],
+            sets: vec![],
+        };
+        assert_eq!((0, 1), step.apply(&mut p));
+        assert_eq!(Some(8), p[0].value());
+        // The digit is gone from every peer.
+        assert!(p
+            .peers(0)
+            .all(|i| p[i].is_solved() || p[i].get() & (1 << 7) == 0));
+        // Placing again doesn't change anything
+        assert_eq!((0, 0), step.apply(&mut p));
+        assert_eq!("Naked Single: r1c1=8", step.to_string());
+    }
+}
@@ -1,13 +1,13 @@
-use crate::candidate_set::CandidateSet;
-use crate::index::ALL_GROUPINGS;
+use crate::fast_index::group_comb_iter;
+use crate::step::{to_digits, Candidate, Step, Technique, Unit};
 use crate::sudoku::Sudoku;
 
 pub trait FindSubset {
-    fn find_subset(&mut self) -> (usize, usize);
+    fn find_subset(&self) -> impl Iterator<Item = Step> + '_;
 }
 
 /// Enum for the result of gen_subset
-/// The usize is the mask of candidates to keep in the affected cells.
+/// The usize is the mask of candidates that make up the subset.
 ///
 /// The effected cells are:
 /// Naked: The cells that don't have the double,triple,quad
@@ -25,13 +25,18 @@ fn gen_subset<T: std::iter::Iterator<Item = usize>>(
     subset: Vec<usize>,
     g_iter: T,
 ) -> Option<Subset> {
+    // Solved positions can't be part of a subset.
+    // They are handled by remove_candidates.
+    if subset.iter().any(|i| sudoku[*i].is_solved()) {
+        return None;
+    }
     let expected_count = subset.len();
     let mask: usize = subset
         .iter()
         .map(|i| sudoku[*i].get_candidates())
         .fold(0, |a, b| a | b);
     let other: usize = g_iter
-        .filter(|i| !subset.iter().any(|s| *i == *s))
+        .filter(|i| !subset.contains(i))
         .map(|i| sudoku[i].get_candidates())
         .fold(0, |a, b| a | b);
     let m_count = mask.count_ones() as usize;
@@ -42,7 +47,7 @@ fn gen_subset<T: std::iter::Iterator<Item = usize>>(
         // If the number of candidates in two unsolved positions is 2
         // and there's at least one occurance in other locations then
         // we know that a NakedDouble can result in some progress.
-        Some(Subset::Naked(!mask, subset))
+        Some(Subset::Naked(mask, subset))
     } else if m_only_count == expected_count && m_count > expected_count {
         // If the number of candidates that are only in this set of tw
         // locations is equal to 2 then they are the only candidat
@@ -53,75 +58,59 @@ fn gen_subset<T: std::iter::Iterator<Item = usize>>(
     }
 }
 
-fn remove_candidate(sudoku: &mut Sudoku, mask: usize, p: usize) -> (usize, usize) {
-    let c = sudoku[p].get() & mask;
-    if c != sudoku[p].get() {
-        sudoku[p] = CandidateSet::new(c);
-        if c.count_ones() == 1 {
-            sudoku[p].set_solved();
-            (1, 1)
-        } else {
-            (1, 0)
-        }
-    } else {
-        (0, 0)
-    }
-}
-
-fn remove_candidates<I: std::iter::Iterator<Item = usize>>(
-    sudoku: &mut Sudoku,
-    mask: usize,
-    g_iter: I,
-) -> Option<(usize, usize)> {
-    let mut changed = 0;
-    let mut solved = 0;
-    for p in g_iter {
-        let (c, s) = remove_candidate(sudoku, mask, p);
-        changed += c;
-        solved += s;
-    }
-    if changed > 0 || solved > 0 {
-        Some((changed, solved))
-    } else {
-        None
-    }
-}
-fn handle_subset<T: std::iter::Iterator<Item = usize>>(
-    sudoku: &mut Sudoku,
-    m: Subset,
-    i: T,
-) -> Option<(usize, usize)> {
-    match m {
+fn subset_step(sudoku: &Sudoku, m: Subset, unit: Unit) -> Option<Step> {
+    let (technique, mask, cells, eliminations) = match m {
         Subset::Naked(mask, v) => {
-            // // For all positions other than the positions in the double remove using the mask
-            remove_candidates(sudoku, mask, i.filter(|p| !v.iter().any(|s| *s == *p)))
+            // For all positions other than the positions in the double remove the mask
+            let eliminations: Vec<Candidate> = sudoku
+                .house(unit)
+                .into_iter()
+                .filter(|p| !v.contains(p) && !sudoku[*p].is_solved())
+                .flat_map(|p| Candidate::from_mask(p, sudoku[p].get() & mask))
+                .collect();
+            (Technique::NakedSubset(v.len()), mask, v, eliminations)
         }
         Subset::Hidden(mask, v) => {
-            // // Remove anything other than the double in the positions in the double
-            remove_candidates(sudoku, mask, v.into_iter())
+            // Remove anything other than the double in the positions in the double
+            let eliminations: Vec<Candidate> = v
+                .iter()
+                .flat_map(|p| Candidate::from_mask(*p, sudoku[*p].get_candidates() & !mask))
+                .collect();
+            (Technique::HiddenSubset(v.len()), mask, v, eliminations)
         }
+    };
+    if eliminations.is_empty() {
+        None
+    } else {
+        Some(Step {
+            technique,
+            units: vec![unit],
+            cells,
+            digits: to_digits(mask),
+            eliminations,
+            placements: vec![],
+            links: vec![],
+            sets: vec![],
+        })
     }
 }
 
 impl FindSubset for Sudoku {
-    fn find_subset(&mut self) -> (usize, usize) {
+    fn find_subset(&self) -> impl Iterator<Item = Step> + '_ {
         // For subset sizes 2,3,4
-        (2..5)
-            .find_map(|sub_size| {
-                // Go throught all the groupings
-                ALL_GROUPINGS.iter().find_map(|g| {
-                    (0..9).find_map(|idx| {
-                        // Then for a grouping get combination iter.
-                        g.sub_iter(idx, sub_size).find_map(|sub| {
-                            // See if this combination of index's has a subset
-                            let found = gen_subset(self, sub, g.iter(idx));
-                            // If it did then remove them and return the results
-                            found.and_then(|s| handle_subset(self, s, g.iter(idx)))
-                        })
-                    })
+        (2..5).flat_map(move |sub_size| {
+            // Go throught all the houses
+            self.houses().flat_map(move |unit| {
+                let cells = self.house(unit);
+                // Then for a house get combination iter.
+                group_comb_iter(sub_size).filter_map(move |sub| {
+                    // See if this combination of index's has a subset
+                    // and if it does turn it into a step.
+                    let sub = sub.into_iter().map(|n| cells[n]).collect();
+                    gen_subset(self, sub, cells.into_iter())
+                        .and_then(|s| subset_step(self, s, unit))
                 })
             })
-            // If nothing else then return 0
-            .unwrap_or((0, 0))
+        })
     }
 }
@@ -1,12 +1,63 @@
+use super::brute_force::{propagate, search};
 use super::candidate_set::CandidateSet;
-use super::index::ALL_GROUPINGS;
+use super::chess::ChessMove;
+use super::error::SudokuErr;
+use super::index::{Grouping, ALL_GROUPINGS};
+use super::index_helpers::get_index_tuple;
+use super::killer::{cage_candidates, Cage};
+use super::region::Region;
+use super::remove_candidates::RemoveCandidates;
+use super::sized::BoxSize;
+use super::step::{Candidate, Unit};
+use std::fmt;
 use std::ops::{Deref, DerefMut};
 
-pub struct Sudoku([CandidateSet; 81]);
+/// A puzzle with `N` digits, 9x9 unless another size is asked
+/// for like `Sudoku<16>`. See `BoxSize` for the sizes.
+///
+/// Every size has the classic rules, singles, and the brute force
+/// search. The solving techniques and the variants are written for
+/// 9x9, so on other sizes there are never any cages, extra regions,
+/// Jigsaw boxes, or chess moves.
+#[derive(Clone)]
+pub struct Sudoku<const N: usize = 9> {
+    positions: Vec<CandidateSet<N>>,
+    /// Killer Sudoku cages. Empty for classic puzzles.
+    cages: Vec<Cage>,
+    /// Extra houses on top of the rows, columns, and boxes.
+    regions: Vec<Region>,
+    /// The box of every position for Jigsaw puzzles.
+    jigsaw: Option<[usize; 81]>,
+    /// Chess moves that can't join two of the same digit.
+    chess: Vec<ChessMove>,
+    /// The positions each position sees, one bit per position, if they
+    /// aren't the classic ones. The setters for regions, Jigsaw boxes,
+    /// and chess moves keep it up to date.
+    peers: Option<Box<[u128; 81]>>,
+}
+
+/// The peers of every position when there are only rows,
+/// columns, and 3x3 boxes.
+const CLASSIC_PEERS: [u128; 81] = classic_peers();
 
-const ALL_POSSIBLE: usize = (1 << 9) - 1;
+const fn classic_peers() -> [u128; 81] {
+    let mut peers = [0; 81];
+    let mut a = 0;
+    while a < 81 {
+        let mut b = 0;
+        while b < 81 {
+            let same_box = a / 27 == b / 27 && a % 9 / 3 == b % 9 / 3;
+            if a != b && (a / 9 == b / 9 || a % 9 == b % 9 || same_box) {
+                peers[a] |= 1 << b;
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    peers
+}
 
-fn valid_group<T: Iterator<Item = usize>>(sudoku: &Sudoku, mut iter: T) -> bool {
+fn valid_group<const N: usize, T: Iterator<Item = usize>>(sudoku: &Sudoku<N>, mut iter: T) -> bool {
     // Set for solved
     let mut s = 0;
     // set for all
@@ -24,56 +75,621 @@ fn valid_group<T: Iterator<Item = usize>>(sudoku: &Sudoku, mut iter: T) -> bool
         } else {
             true
         }
-    }) && a == ALL_POSSIBLE
+    }) && a == CandidateSet::<N>::ALL
 }
 
-impl Deref for Sudoku {
-    type Target = [CandidateSet];
-    fn deref(&self) -> &[CandidateSet] {
-        &self.0
+impl<const N: usize> Deref for Sudoku<N> {
+    type Target = [CandidateSet<N>];
+    fn deref(&self) -> &[CandidateSet<N>] {
+        &self.positions
     }
 }
-impl DerefMut for Sudoku {
-    fn deref_mut(&mut self) -> &mut [CandidateSet] {
-        &mut self.0
+impl<const N: usize> DerefMut for Sudoku<N> {
+    fn deref_mut(&mut self) -> &mut [CandidateSet<N>] {
+        &mut self.positions
     }
 }
 
 impl Sudoku {
     pub fn new(positions: [CandidateSet; 81]) -> Self {
-        Sudoku(positions)
+        Sudoku::from_positions(positions.to_vec())
     }
+
+    /// Turn the puzzle into a Killer Sudoku with these cages.
+    ///
+    /// Each cage has to have between 1 and 9 positions, no position can
+    /// be in more than one cage, and the sum has to be possible with
+    /// different digits.
+    pub fn set_cages(&mut self, cages: Vec<Cage>) -> Result<(), SudokuErr> {
+        let mut used = [false; 81];
+        for cage in cages.iter() {
+            let n = cage.cells.len();
+            // The smallest and largest sums of n different digits.
+            let min = n * (n + 1) / 2;
+            let max = 45 - (9 - n) * (10 - n) / 2;
+            if n == 0 || n > 9 || cage.sum < min || cage.sum > max {
+                return Err(SudokuErr::InvalidCage());
+            }
+            for i in cage.cells.iter() {
+                if *i >= 81 || used[*i] {
+                    return Err(SudokuErr::InvalidCage());
+                }
+                used[*i] = true;
+            }
+        }
+        self.cages = cages;
+        Ok(())
+    }
+
+    /// Add extra houses that have to hold every digit once, like the
+    /// diagonals of X-Sudoku.
+    ///
+    /// Each region has to have nine different positions. The candidates
+    /// aren't touched, call `remove_candidates` to take away the ones
+    /// the regions rule out.
+    pub fn set_regions(&mut self, regions: Vec<Region>) -> Result<(), SudokuErr> {
+        for region in regions.iter() {
+            let mut used = [false; 81];
+            for i in region.cells.iter() {
+                if *i >= 81 || used[*i] {
+                    return Err(SudokuErr::InvalidRegion());
+                }
+                used[*i] = true;
+            }
+        }
+        self.regions = regions;
+        self.update_peers();
+        Ok(())
+    }
+
+    /// Turn the puzzle into a Jigsaw Sudoku with irregular boxes.
+    ///
+    /// `layout` has the box, 0 through 8, of every position. Each box has
+    /// to have nine positions joined up through their sides. Like
+    /// `set_regions` the candidates aren't touched.
+    pub fn set_jigsaw(&mut self, layout: [usize; 81]) -> Result<(), SudokuErr> {
+        for box_i in 0..9 {
+            let cells: Vec<usize> = (0..81).filter(|i| layout[*i] == box_i).collect();
+            if cells.len() != 9 {
+                return Err(SudokuErr::InvalidJigsaw());
+            }
+            // Walk out from the first position without leaving the box.
+            let mut reached = vec![cells[0]];
+            let mut n = 0;
+            while n < reached.len() {
+                let (row, col, _) = get_index_tuple(reached[n]);
+                let sides = [
+                    (row > 0).then(|| reached[n] - 9),
+                    (row < 8).then(|| reached[n] + 9),
+                    (col > 0).then(|| reached[n] - 1),
+                    (col < 8).then(|| reached[n] + 1),
+                ];
+                for i in sides.into_iter().flatten() {
+                    if layout[i] == box_i && !reached.contains(&i) {
+                        reached.push(i);
+                    }
+                }
+                n += 1;
+            }
+            if reached.len() != 9 {
+                return Err(SudokuErr::InvalidJigsaw());
+            }
+        }
+        self.jigsaw = Some(layout);
+        self.update_peers();
+        Ok(())
+    }
+
+    /// Don't allow the same digit a chess move apart anywhere on the
+    /// grid, as in Anti-Knight and Anti-King Sudoku.
+    ///
+    /// Like `set_regions` the candidates aren't touched.
+    pub fn set_chess(&mut self, moves: Vec<ChessMove>) {
+        self.chess = moves;
+        self.update_peers();
+    }
+
+    /// Work out the peers again after the houses or chess moves change.
+    fn update_peers(&mut self) {
+        let mut peers = [0; 81];
+        for unit in self.houses() {
+            let house = self.house(unit);
+            let mask = house.iter().fold(0, |a, i| a | (1 << i));
+            for i in house {
+                peers[i] |= mask;
+            }
+        }
+        for (i, p) in peers.iter_mut().enumerate() {
+            *p |= self.chess_peers(i).fold(0, |a, c| a | (1 << c));
+            *p &= !(1 << i);
+        }
+        self.peers = (peers != CLASSIC_PEERS).then(|| Box::new(peers));
+    }
+
+    /// Do the two positions share a row, column, box, or extra region,
+    /// or are they a chess move apart. A position never sees itself.
+    pub fn sees(&self, a: usize, b: usize) -> bool {
+        self.peer_mask(a) & (1 << b) != 0
+    }
+
+    /// All the positions that share a house with `i` or are a chess move away.
+    pub fn peers(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut mask = self.peer_mask(i);
+        std::iter::from_fn(move || {
+            (mask != 0).then(|| {
+                let p = mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                p
+            })
+        })
+    }
+
+    /// The peers of `i` as a bit mask with one bit per position.
+    pub(crate) fn peer_mask(&self, i: usize) -> u128 {
+        match &self.peers {
+            Some(peers) => peers[i],
+            None => CLASSIC_PEERS[i],
+        }
+    }
+
+    /// Remove `m` from every unsolved position that sees all of `cells`.
+    pub(crate) fn common_peer_eliminations(&self, cells: &[usize], m: usize) -> Vec<Candidate> {
+        self.peers(cells[0])
+            .filter(|i| cells[1..].iter().all(|c| self.sees(*i, *c)))
+            .filter(|i| !cells.contains(i) && !self[*i].is_solved())
+            .flat_map(|i| Candidate::from_mask(i, self[i].get() & m))
+            .collect()
+    }
+
+    /// Every position's candidates laid out as a grid with box borders.
+    ///
+    /// Solved positions are their digit after a `+`, so they can be
+    /// told apart from positions with one candidate left.
+    /// `parse_pencilmarks` reads this back in.
+    pub fn pencilmarks(&self) -> String {
+        let cells: Vec<String> = self
+            .positions
+            .iter()
+            .map(|p| match p.value() {
+                Some(v) => format!("+{}", v),
+                None => p
+                    .into_iter()
+                    .map(|m| (m.trailing_zeros() + 1).to_string())
+                    .collect(),
+            })
+            .collect();
+        // Line up each column with its widest position.
+        let widths: Vec<usize> = (0..9)
+            .map(|col| (0..9).map(|row| cells[row * 9 + col].len()).max().unwrap())
+            .collect();
+        let stacks: Vec<usize> = widths
+            .chunks(3)
+            .map(|w| w.iter().sum::<usize>() + 2)
+            .collect();
+        let border = format!(
+            "{}|{}|{}",
+            "-".repeat(stacks[0] + 1),
+            "-".repeat(stacks[1] + 2),
+            "-".repeat(stacks[2] + 1)
+        );
+        let mut lines = vec![];
+        for row in 0..9 {
+            if row > 0 && row % 3 == 0 {
+                lines.push(border.clone());
+            }
+            let line = (0..3)
+                .map(|stack| {
+                    (stack * 3..stack * 3 + 3)
+                        .map(|col| format!("{:w$}", cells[row * 9 + col], w = widths[col]))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect::<Vec<String>>()
+                .join(" | ");
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl<const N: usize> Sudoku<N> {
+    const BOXES: BoxSize = BoxSize::with_digits(N);
+
+    pub(crate) fn from_positions(positions: Vec<CandidateSet<N>>) -> Self {
+        debug_assert_eq!(N * N, positions.len());
+        Sudoku {
+            positions,
+            cages: vec![],
+            regions: vec![],
+            jigsaw: None,
+            chess: vec![],
+            peers: None,
+        }
+    }
+
+    /// The shape of the boxes.
+    pub fn box_size(&self) -> BoxSize {
+        Self::BOXES
+    }
+
+    /// The Killer Sudoku cages, empty for a classic puzzle.
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// The extra houses, empty for a classic puzzle.
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Which box each position is in for a Jigsaw puzzle,
+    /// or None if the boxes are the usual 3x3 squares.
+    pub fn jigsaw(&self) -> Option<&[usize; 81]> {
+        self.jigsaw.as_ref()
+    }
+
+    /// The chess moves that can't join two of the same digit,
+    /// empty for a classic puzzle.
+    pub fn chess(&self) -> &[ChessMove] {
+        &self.chess
+    }
+
+    /// The box that position `i` is in.
+    pub fn box_of(&self, i: usize) -> usize {
+        match &self.jigsaw {
+            Some(layout) => layout[i],
+            None => Self::BOXES.box_of(i),
+        }
+    }
+
+    /// Every house of the puzzle. The rows, then columns, then boxes,
+    /// then any extra regions.
+    pub fn houses(&self) -> impl Iterator<Item = Unit> + '_ {
+        ALL_GROUPINGS
+            .into_iter()
+            .flat_map(|g| (0..N).map(move |idx| Unit::new(g, idx)))
+            .chain((0..self.regions.len()).map(|idx| Unit::new(Grouping::Region, idx)))
+    }
+
+    /// The positions in a row, column, box, or extra region.
+    pub fn house(&self, unit: Unit) -> [usize; N] {
+        match (unit.grouping, &self.jigsaw) {
+            (Grouping::Region, _) => std::array::from_fn(|k| self.regions[unit.index].cells[k]),
+            (Grouping::Box, Some(layout)) => {
+                let mut cells = [0; N];
+                let in_box = (0..81).filter(|i| layout[*i] == unit.index);
+                for (c, i) in cells.iter_mut().zip(in_box) {
+                    *c = i;
+                }
+                cells
+            }
+            (g, _) => {
+                let mut cells = [0; N];
+                for (c, i) in cells.iter_mut().zip(g.sized_iter(N, unit.index)) {
+                    *c = i;
+                }
+                cells
+            }
+        }
+    }
+
+    /// The extra regions that position `i` is in.
+    pub(crate) fn regions_of(&self, i: usize) -> impl Iterator<Item = &Region> + '_ {
+        self.regions.iter().filter(move |r| r.cells.contains(&i))
+    }
+
+    /// The positions a chess move away from `i`. Some can show up twice.
+    pub(crate) fn chess_peers(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        self.chess.iter().flat_map(move |m| m.reach(i))
+    }
+
     pub fn num_solved(&self) -> usize {
-        self.0.iter().filter(|x| x.is_solved()).count()
+        self.positions.iter().filter(|x| x.is_solved()).count()
     }
 
     pub fn is_solved(&self) -> bool {
-        self.0.iter().all(|x| x.is_solved())
+        self.positions.iter().all(|x| x.is_solved())
     }
 
     pub fn oneline(&self) -> String {
-        self.0
+        self.positions
             .iter()
-            .map(|p| {
-                let v = p.value().unwrap_or(0);
-                match v {
-                    0 => ".".to_string(),
-                    _ => v.to_string(),
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("")
+            .map(|p| p.value().map_or('.', BoxSize::symbol))
+            .collect()
     }
 
     /// Check to see if the puzzle is invalid. Where invalid means:
     ///
-    /// This puzzle doesn't have the same digit twice in a row, col, box.
-    /// Every digit is either in the candidate set or solved in every row, col, box.
+    /// This puzzle doesn't have the same digit twice in a row, col, box, or region.
+    /// Every digit is either in the candidate set or solved in every house.
+    ///
+    /// Every Killer cage can still be filled with different digits
+    /// that add up to its sum.
+    ///
+    /// No digit is solved a chess move away from itself.
     ///
     /// This doesn't 100% mean that the puzzle has a unique solution.
+    /// Use `has_unique_solution` for that.
     pub fn is_valid(&self) -> bool {
-        ALL_GROUPINGS
+        self.houses()
+            .all(|u| valid_group(self, self.house(u).into_iter()))
+            && self
+                .cages
+                .iter()
+                .all(|c| cage_candidates(self, &c.cells, c.sum).is_some())
+            && (0..N * N).filter(|i| self[*i].is_solved()).all(|i| {
+                self.chess_peers(i)
+                    .all(|p| !self[p].is_solved() || self[p].get() != self[i].get())
+            })
+    }
+
+    /// Place naked and hidden singles until there aren't any left.
+    ///
+    /// Returns false if the puzzle turns out to be broken.
+    pub fn solve_singles(&mut self) -> bool {
+        propagate(self)
+    }
+
+    /// Count the solutions to the puzzle by brute force,
+    /// stopping once `limit` of them have been found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 {
+            search(self, &mut |_| {
+                count += 1;
+                count < limit
+            });
+        }
+        count
+    }
+
+    /// Does the puzzle have exactly one solution.
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// The solved positions with all the candidates put back everywhere
+    /// else, except position `skip` which is left empty too.
+    fn givens_without(&self, skip: usize) -> Self {
+        let mut s = self.clone();
+        for (i, set) in s.iter_mut().enumerate() {
+            if i == skip || !set.is_solved() {
+                *set = CandidateSet::new(CandidateSet::<N>::ALL);
+            }
+        }
+        s.remove_candidates(false);
+        s
+    }
+
+    /// The givens that could each be taken away on their own and
+    /// still leave a single solution.
+    ///
+    /// Every solved position counts as a given, the same as `num_solved`.
+    /// If the puzzle doesn't have a single solution then nothing can be
+    /// taken away to give it one.
+    pub fn redundant_givens(&self) -> Vec<usize> {
+        (0..N * N)
+            .filter(|i| self[*i].is_solved())
+            .filter(|i| self.givens_without(*i).has_unique_solution())
+            .collect()
+    }
+
+    /// A puzzle is minimal if it has a single solution and
+    /// taking away any one of the givens allows more.
+    pub fn is_minimal(&self) -> bool {
+        self.has_unique_solution() && self.redundant_givens().is_empty()
+    }
+}
+
+impl Sudoku<16> {
+    /// The puzzle on one line with the digits written 0 to F, the
+    /// way `parse_hex` reads them. Unsolved positions are '.'.
+    pub fn hex_oneline(&self) -> String {
+        self.positions
             .iter()
-            .all(|g| (0..9).all(|idx| valid_group(self, g.iter(idx))))
+            .map(|p| {
+                p.value().map_or('.', |d| {
+                    std::char::from_digit(d as u32 - 1, 16)
+                        .unwrap()
+                        .to_ascii_uppercase()
+                })
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> fmt::Display for Sudoku<N> {
+    /// One row per line with a gap between boxes.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (rows, cols) = (Self::BOXES.rows(), Self::BOXES.cols());
+        let oneline: Vec<char> = self.oneline().chars().collect();
+        for row in 0..N {
+            if row > 0 && row % rows == 0 {
+                writeln!(f)?;
+            }
+            let line = oneline[row * N..(row + 1) * N]
+                .chunks(cols)
+                .map(|c| c.iter().collect::<String>())
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::next_step;
+
+    #[test]
+    fn test_count_solutions() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        assert_eq!(1, p.count_solutions(10));
+        assert!(p.has_unique_solution());
+
+        // A solved grid missing a rectangle where the digits can swap.
+        let s = "8196.7.255278.1.69643529178476218953135796284298354716351962847764183592982475631";
+        let p = parse_sudoku(s).unwrap();
+        assert!(!p.has_unique_solution());
+        assert_eq!(2, p.count_solutions(5));
+        assert_eq!(1, p.count_solutions(1));
+        assert_eq!(0, p.count_solutions(0));
+    }
+
+    #[test]
+    fn test_pencilmarks() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let marks = p.pencilmarks();
+        assert_eq!(11, marks.lines().count());
+        let q = parse_pencilmarks(&marks).unwrap();
+        assert!((0..81).all(|i| p[i].get_candidates() == q[i].get_candidates()));
+        assert_eq!(marks, q.pencilmarks());
+
+        let s = "819637425527841369643529178476218953135796284298354716351962847764183592982475631";
+        let p = parse_sudoku(s).unwrap();
+        let marks = "+8 +1 +9 | +6 +3 +7 | +4 +2 +5
++5 +2 +7 | +8 +4 +1 | +3 +6 +9
++6 +4 +3 | +5 +2 +9 | +1 +7 +8
+---------|----------|---------
++4 +7 +6 | +2 +1 +8 | +9 +5 +3
++1 +3 +5 | +7 +9 +6 | +2 +8 +4
++2 +9 +8 | +3 +5 +4 | +7 +1 +6
+---------|----------|---------
++3 +5 +1 | +9 +6 +2 | +8 +4 +7
++7 +6 +4 | +1 +8 +3 | +5 +9 +2
++9 +8 +2 | +4 +7 +5 | +6 +3 +1";
+        assert_eq!(marks, p.pencilmarks());
+    }
+
+    #[test]
+    fn test_pencilmarks_mid_solve() {
+        let mut p = parse_sudoku(HARD).unwrap();
+        // A naked single that hasn't been placed yet.
+        assert!((0..81).any(|i| !p[i].is_solved() && p[i].num_candidates() == 1));
+        for steps in [0, 20] {
+            for _ in 0..steps {
+                next_step(&p).unwrap().apply(&mut p);
+            }
+            let q = parse_pencilmarks(&p.pencilmarks()).unwrap();
+            assert!((0..81).all(|i| p[i].get() == q[i].get()));
+            assert_eq!(p.num_solved(), q.num_solved());
+            assert_eq!(p.pencilmarks(), q.pencilmarks());
+        }
+    }
+
+    #[test]
+    fn test_redundant_givens() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        assert!(p.redundant_givens().is_empty());
+        assert!(p.is_minimal());
+
+        // Add r1c1 from the solution. Either it or r2c4 can be taken away.
+        let s = "81.....25.2.84.3.9.....91.84.....9.3.....6.......5....35..6.....6.1.....98......1";
+        let p = parse_sudoku(s).unwrap();
+        assert_eq!(vec![0, 12], p.redundant_givens());
+        assert!(!p.is_minimal());
+
+        // Everything in a solved grid is redundant.
+        let s = "819637425527841369643529178476218953135796284298354716351962847764183592982475631";
+        let p = parse_sudoku(s).unwrap();
+        assert_eq!(81, p.redundant_givens().len());
+
+        // More than one solution is never minimal.
+        let s = "8196.7.255278.1.69643529178476218953135796284298354716351962847764183592982475631";
+        let p = parse_sudoku(s).unwrap();
+        assert!(p.redundant_givens().is_empty());
+        assert!(!p.is_minimal());
+    }
+
+    #[test]
+    fn test_jigsaw() {
+        let p = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        assert_eq!(0, p.box_of(0));
+        // r2c3 is in the second box, r2c5 in the fifth.
+        assert_eq!(1, p.box_of(11));
+        assert_eq!(4, p.box_of(13));
+        assert_eq!(
+            [0, 1, 2, 9, 10, 18, 19, 20, 28],
+            p.house(Unit::new(Grouping::Box, 0))
+        );
+        assert!(p.sees(0, 28));
+        assert!(!p.sees(0, 21));
+        // The usual boxes don't apply.
+        assert_ne!(1, parse_sudoku(JIGSAW).unwrap().count_solutions(2));
+
+        let empty = ".".repeat(81);
+        // A box made of two pieces.
+        let layout = JIGSAW_LAYOUT.replacen("111222333", "121122333", 1);
+        let mut q = parse_sudoku(&empty).unwrap();
+        let mut boxes = [0; 81];
+        for (b, c) in boxes
+            .iter_mut()
+            .zip(layout.chars().filter(|c| c.is_ascii_digit()))
+        {
+            *b = c.to_digit(10).unwrap() as usize - 1;
+        }
+        assert!(matches!(
+            q.set_jigsaw(boxes),
+            Err(SudokuErr::InvalidJigsaw())
+        ));
+        // Ten positions in a box.
+        boxes[2] = 0;
+        assert!(matches!(
+            q.set_jigsaw(boxes),
+            Err(SudokuErr::InvalidJigsaw())
+        ));
+        assert!(q.jigsaw().is_none());
+        assert!(matches!(
+            parse_jigsaw(&empty, &layout),
+            Err(SudokuErr::InvalidJigsaw())
+        ));
+        // Only eight boxes.
+        let layout = JIGSAW_LAYOUT.replace('9', "8");
+        assert!(matches!(
+            parse_jigsaw(&empty, &layout),
+            Err(SudokuErr::Parse())
+        ));
+    }
+
+    #[test]
+    fn test_peers() {
+        let classic = parse_sudoku(ONE_LINE).unwrap();
+        let mut chess = classic.clone();
+        chess.set_chess(vec![ChessMove::Knight, ChessMove::King]);
+        let puzzles = [
+            classic,
+            parse_sudoku_with_regions(WINDOKU, Region::windoku()).unwrap(),
+            parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap(),
+            chess,
+        ];
+        for p in puzzles {
+            for a in 0..81 {
+                let (row_a, col_a, _) = get_index_tuple(a);
+                let peers: Vec<usize> = (0..81)
+                    .filter(|b| {
+                        let (row_b, col_b, _) = get_index_tuple(*b);
+                        a != *b
+                            && (row_a == row_b
+                                || col_a == col_b
+                                || p.box_of(a) == p.box_of(*b)
+                                || p.regions_of(a).any(|r| r.cells.contains(b))
+                                || p.chess().iter().any(|m| m.connects(a, *b)))
+                    })
+                    .collect();
+                assert_eq!(peers, p.peers(a).collect::<Vec<usize>>());
+                assert!(peers.iter().all(|b| p.sees(a, *b) && p.sees(*b, a)));
+            }
+        }
+        // Going back to the classic peers.
+        let mut p = parse_sudoku(ONE_LINE).unwrap();
+        p.set_chess(vec![ChessMove::King]);
+        assert!(p.sees(2, 12));
+        p.set_chess(vec![]);
+        assert!(!p.sees(2, 12));
+        assert_eq!(20, p.peers(40).count());
     }
 }
@@ -0,0 +1,38 @@
+use crate::candidate_set::CandidateSet;
+use crate::parse::parse_sudoku;
+use crate::solve::next_step;
+use crate::step::{Step, Technique};
+use crate::sudoku::Sudoku;
+
+/// A puzzle where 1 is only a candidate in `ones`
+/// and everything else is open.
+pub fn with_ones(ones: &[usize]) -> Sudoku {
+    let mut p = [CandidateSet::new((1 << 9) - 2); 81];
+    for i in ones.iter() {
+        p[*i] = CandidateSet::new((1 << 9) - 1);
+    }
+    Sudoku::new(p)
+}
+
+/// A puzzle with every candidate open other
+/// than the positions given as masks.
+pub fn with_masks(masks: &[(usize, usize)]) -> Sudoku {
+    let mut p = [CandidateSet::new((1 << 9) - 1); 81];
+    for (i, m) in masks.iter() {
+        p[*i] = CandidateSet::new(*m);
+    }
+    Sudoku::new(p)
+}
+
+/// Apply steps from `s` until `technique` is the next one.
+/// Returns the puzzle as it is then along with the step.
+pub fn step_until(s: &str, technique: Technique) -> (Sudoku, Step) {
+    let mut p = parse_sudoku(s).unwrap();
+    loop {
+        let step = next_step(&p).unwrap();
+        if step.technique == technique {
+            return (p, step);
+        }
+        step.apply(&mut p);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::candidate_set::{CandidateSet, CandidateSetIterator};
+use crate::index_helpers::{get_index_tuple, to_index};
+use crate::killer::Cage;
+use crate::region::Region;
+use crate::sudoku::Sudoku;
+
+/// Changes to a puzzle that keep it valid, and keep the same
+/// number of solutions.
+///
+/// Everything moves along with its candidates so a partly
+/// solved puzzle stays partly solved. Rows, columns, bands,
+/// and stacks are numbered from 0.
+pub trait Transform {
+    /// Relabel the digits so that `d` becomes `digits[d - 1]`.
+    ///
+    /// Killer cage sums would change so this isn't allowed for Killer puzzles.
+    fn permute_digits(&mut self, digits: [usize; 9]);
+    /// Swap two rows in the same band.
+    ///
+    /// Swapping rows, columns, bands, or stacks would break up
+    /// Jigsaw boxes and change which positions are a chess move
+    /// apart, so they aren't allowed for those puzzles.
+    fn swap_rows(&mut self, a: usize, b: usize);
+    /// Swap two columns in the same stack.
+    fn swap_columns(&mut self, a: usize, b: usize);
+    /// Swap two bands of three rows.
+    fn swap_bands(&mut self, a: usize, b: usize);
+    /// Swap two stacks of three columns.
+    fn swap_stacks(&mut self, a: usize, b: usize);
+    /// Mirror across the diagonal from r1c1 to r9c9.
+    fn transpose(&mut self);
+    /// Turn a quarter of the way around clockwise.
+    fn rotate(&mut self);
+}
+
+/// Move every position to where `from` says it comes from.
+/// Killer cages, extra regions, and Jigsaw boxes move along with their cells.
+/// Chess moves look the same after a rotation or transpose.
+fn rearrange(sudoku: &mut Sudoku, from: impl Fn(usize, usize) -> (usize, usize)) {
+    let old = sudoku.clone();
+    let mut moved_to = [0; 81];
+    for i in 0..81 {
+        let (row_i, col_i, _) = get_index_tuple(i);
+        let (r, c) = from(row_i, col_i);
+        sudoku[i] = old[to_index(r, c)];
+        moved_to[to_index(r, c)] = i;
+    }
+    if !old.cages().is_empty() {
+        let cages = old
+            .cages()
+            .iter()
+            .map(|c| Cage::new(c.sum, c.cells.iter().map(|i| moved_to[*i]).collect()))
+            .collect();
+        // Moving cells around can't make the cages overlap.
+        sudoku.set_cages(cages).unwrap();
+    }
+    if !old.regions().is_empty() {
+        let regions = old
+            .regions()
+            .iter()
+            .map(|r| Region::new(r.cells.map(|i| moved_to[i])))
+            .collect();
+        sudoku.set_regions(regions).unwrap();
+    }
+    if let Some(layout) = old.jigsaw() {
+        let moved = std::array::from_fn(|i| {
+            let (row_i, col_i, _) = get_index_tuple(i);
+            let (r, c) = from(row_i, col_i);
+            layout[to_index(r, c)]
+        });
+        sudoku.set_jigsaw(moved).unwrap();
+    }
+}
+
+/// `a` and `b` swapped, everything else left alone.
+fn swapped(x: usize, a: usize, b: usize) -> usize {
+    match x {
+        _ if x == a => b,
+        _ if x == b => a,
+        _ => x,
+    }
+}
+
+/// The same three lines from band or stack `a` and `b` swapped.
+fn swapped_group(x: usize, a: usize, b: usize) -> usize {
+    swapped(x / 3, a, b) * 3 + x % 3
+}
+
+impl Transform for Sudoku {
+    fn permute_digits(&mut self, digits: [usize; 9]) {
+        let all = digits.iter().fold(0, |m, d| m | 1 << (d - 1));
+        assert_eq!((1 << 9) - 1, all, "Every digit must be used once");
+        assert!(self.cages().is_empty(), "Killer cages can't be relabelled");
+        for i in 0..81 {
+            let old = self[i];
+            let m = CandidateSetIterator::new(old.get_candidates())
+                .map(|m| 1 << (digits[m.trailing_zeros() as usize] - 1))
+                .fold(0, |a, b| a | b);
+            self[i] = CandidateSet::new(m | (old.get() & !old.get_candidates()));
+        }
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        assert!(self.jigsaw().is_none(), "Jigsaw boxes can't be split up");
+        assert!(self.chess().is_empty(), "Chess moves can't be pulled apart");
+        assert!(a < 9 && b < 9, "There are only 9 rows");
+        assert_eq!(a / 3, b / 3, "Rows must be in the same band");
+        rearrange(self, |r, c| (swapped(r, a, b), c));
+    }
+
+    fn swap_columns(&mut self, a: usize, b: usize) {
+        assert!(self.jigsaw().is_none(), "Jigsaw boxes can't be split up");
+        assert!(self.chess().is_empty(), "Chess moves can't be pulled apart");
+        assert!(a < 9 && b < 9, "There are only 9 columns");
+        assert_eq!(a / 3, b / 3, "Columns must be in the same stack");
+        rearrange(self, |r, c| (r, swapped(c, a, b)));
+    }
+
+    fn swap_bands(&mut self, a: usize, b: usize) {
+        assert!(self.jigsaw().is_none(), "Jigsaw boxes can't be split up");
+        assert!(self.chess().is_empty(), "Chess moves can't be pulled apart");
+        assert!(a < 3 && b < 3, "There are only 3 bands");
+        rearrange(self, |r, c| (swapped_group(r, a, b), c));
+    }
+
+    fn swap_stacks(&mut self, a: usize, b: usize) {
+        assert!(self.jigsaw().is_none(), "Jigsaw boxes can't be split up");
+        assert!(self.chess().is_empty(), "Chess moves can't be pulled apart");
+        assert!(a < 3 && b < 3, "There are only 3 stacks");
+        rearrange(self, |r, c| (r, swapped_group(c, a, b)));
+    }
+
+    fn transpose(&mut self) {
+        rearrange(self, |r, c| (c, r));
+    }
+
+    fn rotate(&mut self) {
+        rearrange(self, |r, c| (8 - c, r));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::Canonical;
+    use crate::examples::*;
+    use crate::parse::*;
+    use crate::solve::next_step;
+
+    const SOLVED: &str =
+        "819637425527841369643529178476218953135796284298354716351962847764183592982475631";
+
+    #[test]
+    fn test_permute_digits() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.permute_digits([9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert!(p.is_solved() && p.is_valid());
+        assert_eq!("291473685", &p.oneline()[..9]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permute_digits_not_a_permutation() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.permute_digits([1, 1, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_swaps() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_rows(0, 2);
+        assert_eq!("643529178", &p.oneline()[..9]);
+        p.swap_bands(0, 2);
+        assert_eq!("643529178", &p.oneline()[54..63]);
+        p.swap_columns(3, 5);
+        assert_eq!("643925178", &p.oneline()[54..63]);
+        p.swap_stacks(1, 2);
+        assert_eq!("643178925", &p.oneline()[54..63]);
+        assert!(p.is_valid());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_rows_across_bands() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_rows(2, 3);
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let p = parse_sudoku(ONE_LINE).unwrap();
+        let mut q = p.clone();
+        q.transpose();
+        assert_eq!(p[1].get(), q[9].get());
+        q.transpose();
+        assert_eq!(p.oneline(), q.oneline());
+        q.rotate();
+        // r1c1 moves to r1c9.
+        assert_eq!(p[0].get(), q[8].get());
+        assert_eq!(p[1].get(), q[17].get());
+        for _ in 0..3 {
+            q.rotate();
+        }
+        assert_eq!(p.oneline(), q.oneline());
+    }
+
+    #[test]
+    fn test_transform_keeps_candidates() {
+        let mut p = parse_sudoku(HARD).unwrap();
+        for _ in 0..10 {
+            next_step(&p).unwrap().apply(&mut p);
+        }
+        let mut q = p.clone();
+        q.permute_digits([2, 3, 4, 5, 6, 7, 8, 9, 1]);
+        q.swap_rows(3, 4);
+        q.swap_stacks(0, 1);
+        q.rotate();
+        assert!(q.is_valid());
+        assert!(q.is_isomorphic(&p));
+        // Undo everything and the candidates are all still there.
+        for _ in 0..3 {
+            q.rotate();
+        }
+        q.swap_stacks(0, 1);
+        q.swap_rows(3, 4);
+        q.permute_digits([9, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!((0..81).all(|i| p[i].get() == q[i].get()));
+    }
+
+    #[test]
+    fn test_transform_moves_jigsaw() {
+        let p = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        let mut q = p.clone();
+        q.rotate();
+        q.transpose();
+        assert!(q.is_valid());
+        // r1c1 went to r1c9 then to r9c1.
+        assert_eq!(p.box_of(0), q.box_of(72));
+        while let Some(step) = next_step(&q) {
+            step.apply(&mut q);
+        }
+        assert!(q.is_solved());
+        // Undo everything and it's the original solution.
+        q.transpose();
+        for _ in 0..3 {
+            q.rotate();
+        }
+        assert_eq!(p.jigsaw(), q.jigsaw());
+        assert_eq!(
+            "469528137874631952351947268726813549513289674942756381297465813185372496638194725",
+            q.oneline()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_rows_out_of_range() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_rows(9, 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_columns_out_of_range() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_columns(9, 11);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_bands_out_of_range() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_bands(0, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_stacks_out_of_range() {
+        let mut p = parse_sudoku(SOLVED).unwrap();
+        p.swap_stacks(3, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_rows_jigsaw() {
+        let mut p = parse_jigsaw(JIGSAW, JIGSAW_LAYOUT).unwrap();
+        p.swap_rows(0, 2);
+    }
+}
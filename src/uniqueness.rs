@@ -0,0 +1,551 @@
+use crate::candidate_set::CandidateSetIterator;
+use crate::index::{Grouping, ALL_GROUPINGS};
+use crate::index_helpers::*;
+use crate::step::{to_digit, to_digits, Candidate, Step, Technique, Unit};
+use crate::sudoku::Sudoku;
+
+/// Techniques that are only valid if the puzzle has a single solution.
+///
+/// Only classic puzzles are checked, not ones with Killer cages,
+/// extra regions, or Jigsaw boxes.
+pub trait Uniqueness {
+    fn find_unique_rectangle(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_bug_plus_one(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// Four unsolved positions on two rows, two columns, and two boxes
+/// that all have the same pair of digits.
+struct Rectangle {
+    /// The corners in the order r1c1, r1c2, r2c1, r2c2.
+    cells: [usize; 4],
+    rows: [usize; 2],
+    cols: [usize; 2],
+    /// The two digits as a mask.
+    pair: usize,
+}
+
+impl Rectangle {
+    /// The corner diagonally across from `i`.
+    fn opposite(&self, i: usize) -> usize {
+        let n = self.cells.iter().position(|c| *c == i).unwrap();
+        self.cells[3 - n]
+    }
+}
+
+fn rectangles(sudoku: &Sudoku) -> Vec<Rectangle> {
+    let mut res = Vec::new();
+    for r1 in 0..9 {
+        for r2 in r1 + 1..9 {
+            for c1 in 0..9 {
+                for c2 in c1 + 1..9 {
+                    // Exactly two boxes; four would let the digits swap.
+                    if (r1 / 3 == r2 / 3) == (c1 / 3 == c2 / 3) {
+                        continue;
+                    }
+                    let cells = [
+                        to_index(r1, c1),
+                        to_index(r1, c2),
+                        to_index(r2, c1),
+                        to_index(r2, c2),
+                    ];
+                    if cells.iter().any(|i| sudoku[*i].is_solved()) {
+                        continue;
+                    }
+                    let common = cells
+                        .iter()
+                        .fold((1 << 9) - 1, |a, i| a & sudoku[*i].get_candidates());
+                    let digits: Vec<usize> = CandidateSetIterator::new(common).collect();
+                    for (n, a) in digits.iter().enumerate() {
+                        for b in digits[n + 1..].iter() {
+                            res.push(Rectangle {
+                                cells,
+                                rows: [r1, r2],
+                                cols: [c1, c2],
+                                pair: a | b,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Unsolved positions in unit `g`/`g_idx` that have `m`.
+fn with_digit(sudoku: &Sudoku, g: Grouping, g_idx: usize, m: usize) -> Vec<usize> {
+    g.iter(g_idx)
+        .filter(|i| !sudoku[*i].is_solved() && sudoku[*i].get() & m != 0)
+        .collect()
+}
+
+/// The units that have both `a` and `b` in them.
+fn shared_units(a: usize, b: usize) -> Vec<Unit> {
+    let (row_a, col_a, box_a) = get_index_tuple(a);
+    let (row_b, col_b, box_b) = get_index_tuple(b);
+    let mut units = Vec::new();
+    if row_a == row_b {
+        units.push(Unit::new(Grouping::Row, row_a));
+    }
+    if col_a == col_b {
+        units.push(Unit::new(Grouping::Column, col_a));
+    }
+    if box_a == box_b {
+        units.push(Unit::new(Grouping::Box, box_a));
+    }
+    units
+}
+
+fn ur_step(
+    rect: &Rectangle,
+    kind: usize,
+    units: Vec<Unit>,
+    extra_cells: &[usize],
+    eliminations: Vec<Candidate>,
+) -> Option<Step> {
+    if eliminations.is_empty() {
+        return None;
+    }
+    let technique = match kind {
+        0 => Technique::HiddenUniqueRectangle,
+        _ => Technique::UniqueRectangle(kind),
+    };
+    Some(Step {
+        technique,
+        units,
+        cells: rect.cells.iter().chain(extra_cells).cloned().collect(),
+        digits: to_digits(rect.pair),
+        eliminations,
+        placements: vec![],
+        links: vec![],
+        sets: vec![],
+    })
+}
+
+/// The extra digits of the roofs would make a naked subset with
+/// other positions in a unit the roofs share.
+fn type_3_steps(sudoku: &Sudoku, rect: &Rectangle, roofs: &[usize], extras: usize) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for unit in shared_units(roofs[0], roofs[1]) {
+        let others: Vec<usize> = unit
+            .grouping
+            .iter(unit.index)
+            .filter(|i| !sudoku[*i].is_solved() && !rect.cells.contains(i))
+            .collect();
+        for sel in 1usize..(1 << others.len()) {
+            let size = sel.count_ones() as usize;
+            if size > 3 {
+                continue;
+            }
+            let subset: Vec<usize> = (0..others.len())
+                .filter(|n| sel & (1 << n) != 0)
+                .map(|n| others[n])
+                .collect();
+            let m = subset.iter().fold(extras, |a, i| a | sudoku[*i].get());
+            if m.count_ones() as usize != size + 1 {
+                continue;
+            }
+            let eliminations = others
+                .iter()
+                .filter(|i| !subset.contains(i))
+                .flat_map(|i| Candidate::from_mask(*i, sudoku[*i].get() & m))
+                .collect();
+            steps.extend(ur_step(rect, 3, vec![unit], &subset, eliminations));
+        }
+    }
+    steps
+}
+
+/// All the ways `rect` can be kept from becoming a deadly pattern.
+fn rectangle_steps(sudoku: &Sudoku, rect: &Rectangle) -> Vec<Step> {
+    let pair = rect.pair;
+    let (floors, roofs): (Vec<usize>, Vec<usize>) = rect
+        .cells
+        .iter()
+        .partition(|i| sudoku[**i].get_candidates() == pair);
+    let extras = roofs
+        .iter()
+        .fold(0, |a, i| a | (sudoku[*i].get_candidates() & !pair));
+    // Every roof has the same one extra digit.
+    let single_extra =
+        extras.count_ones() == 1 && roofs.iter().all(|i| sudoku[*i].num_candidates() == 3);
+    let mut steps = Vec::new();
+
+    match roofs.len() {
+        1 => {
+            // The roof can't be either digit.
+            let eliminations = Candidate::from_mask(roofs[0], pair).collect();
+            steps.extend(ur_step(rect, 1, vec![], &[], eliminations));
+        }
+        2 if !shared_units(roofs[0], roofs[1]).is_empty() => {
+            if single_extra {
+                let eliminations = sudoku.common_peer_eliminations(&roofs, extras);
+                steps.extend(ur_step(rect, 2, vec![], &[], eliminations));
+            }
+            steps.extend(type_3_steps(sudoku, rect, &roofs, extras));
+            // If one digit is only in the roofs for a unit they share
+            // then the other digit can't be in either of them.
+            for unit in shared_units(roofs[0], roofs[1]) {
+                for u in CandidateSetIterator::new(pair) {
+                    let cells = with_digit(sudoku, unit.grouping, unit.index, u);
+                    if cells.len() == 2 && cells.iter().all(|i| roofs.contains(i)) {
+                        let eliminations = roofs
+                            .iter()
+                            .flat_map(|i| Candidate::from_mask(*i, pair & !u))
+                            .collect();
+                        steps.extend(ur_step(rect, 4, vec![unit], &[], eliminations));
+                    }
+                }
+            }
+        }
+        2 => {
+            if single_extra {
+                let eliminations = sudoku.common_peer_eliminations(&roofs, extras);
+                steps.extend(ur_step(rect, 5, vec![], &[], eliminations));
+            }
+            // If a digit is only in the rectangle for both rows or both
+            // columns, it would have to be in both roofs or both floors.
+            for u in CandidateSetIterator::new(pair) {
+                for (g, lines) in [(Grouping::Row, rect.rows), (Grouping::Column, rect.cols)] {
+                    if lines
+                        .iter()
+                        .all(|l| with_digit(sudoku, g, *l, u).len() == 2)
+                    {
+                        let eliminations = roofs
+                            .iter()
+                            .flat_map(|i| Candidate::from_mask(*i, u))
+                            .collect();
+                        let units = lines.iter().map(|l| Unit::new(g, *l)).collect();
+                        steps.extend(ur_step(rect, 6, units, &[], eliminations));
+                    }
+                }
+            }
+        }
+        3 if single_extra => {
+            let eliminations = sudoku.common_peer_eliminations(&roofs, extras);
+            steps.extend(ur_step(rect, 5, vec![], &[], eliminations));
+        }
+        _ => {}
+    }
+
+    // Hidden rectangle: if a digit is only in the rectangle for the row
+    // and column of the corner across from a floor, that corner
+    // can't be the other digit.
+    for floor in floors.iter() {
+        let corner = rect.opposite(*floor);
+        if sudoku[corner].get_candidates() == pair {
+            continue;
+        }
+        let (row, col, _) = get_index_tuple(corner);
+        for u in CandidateSetIterator::new(pair) {
+            if with_digit(sudoku, Grouping::Row, row, u).len() == 2
+                && with_digit(sudoku, Grouping::Column, col, u).len() == 2
+            {
+                let eliminations = Candidate::from_mask(corner, pair & !u).collect();
+                let units = vec![
+                    Unit::new(Grouping::Row, row),
+                    Unit::new(Grouping::Column, col),
+                ];
+                steps.extend(ur_step(rect, 0, units, &[], eliminations));
+            }
+        }
+    }
+    steps
+}
+
+/// Bivalue Universal Grave + 1: every unsolved position has two
+/// candidates other than one with three. Without the extra digit every
+/// digit would be in each unit exactly twice and the puzzle would have
+/// two solutions. So the extra digit is the solution for that position.
+fn bug_plus_one(sudoku: &Sudoku) -> Option<Step> {
+    let mut triple = None;
+    for i in (0..81).filter(|i| !sudoku[*i].is_solved()) {
+        match sudoku[i].num_candidates() {
+            2 => {}
+            3 if triple.is_none() => triple = Some(i),
+            _ => return None,
+        }
+    }
+    let triple = triple?;
+    CandidateSetIterator::new(sudoku[triple].get_candidates())
+        .find(|m| {
+            // Taking the digit out leaves every digit twice in every unit.
+            ALL_GROUPINGS.iter().all(|g| {
+                (0..9).all(|g_idx| {
+                    CandidateSetIterator::new((1 << 9) - 1).all(|d| {
+                        let n = with_digit(sudoku, *g, g_idx, d)
+                            .iter()
+                            .filter(|i| !(**i == triple && d == *m))
+                            .count();
+                        n == 0 || n == 2
+                    })
+                })
+            })
+        })
+        .map(|m| Step {
+            technique: Technique::BugPlusOne,
+            units: vec![],
+            cells: vec![triple],
+            digits: vec![to_digit(m)],
+            eliminations: vec![],
+            placements: vec![Candidate::new(triple, to_digit(m))],
+            links: vec![],
+            sets: vec![],
+        })
+}
+
+/// Swapping digits around a deadly pattern can break a Killer cage's
+/// sum, an extra region, or a chess move, so it might not be deadly
+/// there. The rectangles also need the usual 3x3 boxes.
+fn is_classic(sudoku: &Sudoku) -> bool {
+    sudoku.cages().is_empty()
+        && sudoku.regions().is_empty()
+        && sudoku.jigsaw().is_none()
+        && sudoku.chess().is_empty()
+}
+
+impl Uniqueness for Sudoku {
+    fn find_unique_rectangle(&self) -> impl Iterator<Item = Step> + '_ {
+        let rects = if is_classic(self) {
+            rectangles(self)
+        } else {
+            vec![]
+        };
+        rects
+            .into_iter()
+            .flat_map(move |rect| rectangle_steps(self, &rect))
+    }
+
+    fn find_bug_plus_one(&self) -> impl Iterator<Item = Step> + '_ {
+        is_classic(self)
+            .then(|| bug_plus_one(self))
+            .flatten()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidate_set::CandidateSet;
+    use crate::parse::*;
+    use crate::solve::{next_step, next_step_with, SolveOptions, Solveable};
+    use crate::test_helpers::with_masks;
+
+    #[test]
+    fn test_unique_rectangle_type_1() {
+        // r1c1, r1c5, and r2c1 are 1 or 2 so r2c5 can't be either.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b011),
+            (to_index(0, 4), 0b011),
+            (to_index(1, 0), 0b011),
+            (to_index(1, 4), 0b111),
+        ]);
+        let step = p.find_unique_rectangle().next().unwrap();
+        assert_eq!(Technique::UniqueRectangle(1), step.technique);
+        assert_eq!(vec![1, 2], step.digits);
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(1, 4), 1),
+                Candidate::new(to_index(1, 4), 2)
+            ],
+            step.eliminations
+        );
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_2() {
+        // Both roofs are 1, 2, or 3, so one of them is 3.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b011),
+            (to_index(0, 4), 0b011),
+            (to_index(1, 0), 0b111),
+            (to_index(1, 4), 0b111),
+        ]);
+        let step = p.find_unique_rectangle().next().unwrap();
+        assert_eq!(Technique::UniqueRectangle(2), step.technique);
+        // Everything else in row 2 loses the 3.
+        assert_eq!(7, step.eliminations.len());
+        assert!(step.eliminations.iter().all(|c| c.digit == 3));
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_3() {
+        // The roofs' 3 and 4 make a naked pair with r2c9.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b0011),
+            (to_index(0, 4), 0b0011),
+            (to_index(1, 0), 0b0111),
+            (to_index(1, 4), 0b1011),
+            (to_index(1, 8), 0b1100),
+        ]);
+        let steps: Vec<Step> = p
+            .find_unique_rectangle()
+            .filter(|s| s.technique == Technique::UniqueRectangle(3))
+            .collect();
+        assert_eq!(1, steps.len());
+        assert_eq!(vec![Unit::new(Grouping::Row, 1)], steps[0].units);
+        // The rest of row 2 loses the 3 and 4.
+        let eliminations: Vec<Candidate> = [1, 2, 3, 5, 6, 7]
+            .iter()
+            .flat_map(|c| [3, 4].map(|d| Candidate::new(to_index(1, *c), d)))
+            .collect();
+        assert_eq!(eliminations, steps[0].eliminations);
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_4() {
+        // 1 is only in the roofs for row 2, so they can't be 2.
+        let mut masks: Vec<(usize, usize)> =
+            (0..9).map(|c| (to_index(1, c), 0b1_1111_1110)).collect();
+        masks.extend([
+            (to_index(0, 0), 0b0011),
+            (to_index(0, 4), 0b0011),
+            (to_index(1, 0), 0b0111),
+            (to_index(1, 4), 0b1011),
+        ]);
+        let p = with_masks(&masks);
+        let steps: Vec<Step> = p
+            .find_unique_rectangle()
+            .filter(|s| s.technique == Technique::UniqueRectangle(4))
+            .collect();
+        assert_eq!(1, steps.len());
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(1, 0), 2),
+                Candidate::new(to_index(1, 4), 2)
+            ],
+            steps[0].eliminations
+        );
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_5() {
+        // The roofs are diagonal and one of them is 3.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b011),
+            (to_index(0, 4), 0b111),
+            (to_index(1, 0), 0b111),
+            (to_index(1, 4), 0b011),
+        ]);
+        let steps: Vec<Step> = p
+            .find_unique_rectangle()
+            .filter(|s| s.technique == Technique::UniqueRectangle(5))
+            .collect();
+        assert_eq!(1, steps.len());
+        let mut eliminations = steps[0].eliminations.clone();
+        eliminations.sort_by_key(|c| c.index);
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(0, 1), 3),
+                Candidate::new(to_index(0, 2), 3),
+                Candidate::new(to_index(1, 3), 3),
+                Candidate::new(to_index(1, 5), 3)
+            ],
+            eliminations
+        );
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_6() {
+        // 1 is only in the rectangle for rows 1 and 2, so it's in
+        // both floors and neither roof.
+        let mut masks: Vec<(usize, usize)> = (0..18).map(|i| (i, 0b1_1111_1110)).collect();
+        masks.extend([
+            (to_index(0, 0), 0b0011),
+            (to_index(0, 4), 0b1111),
+            (to_index(1, 0), 0b1111),
+            (to_index(1, 4), 0b0011),
+        ]);
+        let p = with_masks(&masks);
+        let steps: Vec<Step> = p
+            .find_unique_rectangle()
+            .filter(|s| s.technique == Technique::UniqueRectangle(6))
+            .collect();
+        assert_eq!(1, steps.len());
+        assert_eq!(
+            vec![Unit::new(Grouping::Row, 0), Unit::new(Grouping::Row, 1)],
+            steps[0].units
+        );
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(0, 4), 1),
+                Candidate::new(to_index(1, 0), 1)
+            ],
+            steps[0].eliminations
+        );
+    }
+
+    #[test]
+    fn test_no_unique_rectangle_in_four_boxes() {
+        let p = with_masks(&[
+            (to_index(0, 0), 0b011),
+            (to_index(0, 4), 0b011),
+            (to_index(4, 0), 0b011),
+            (to_index(4, 4), 0b111),
+        ]);
+        assert!(p.find_unique_rectangle().next().is_none());
+    }
+
+    #[test]
+    fn test_bug_plus_one() {
+        // Every position is 1 or 2, other than a single 3 in
+        // r1c1. Without the 3 there would be two solutions.
+        let mut p = [CandidateSet::new(0); 81];
+        for (i, c) in p.iter_mut().enumerate() {
+            let (row, col, _) = get_index_tuple(i);
+            let v = (row * 3 + row / 3 + col) % 9;
+            *c = if v < 2 {
+                CandidateSet::new(0b011)
+            } else {
+                let mut s = CandidateSet::new(1 << v);
+                s.set_solved();
+                s
+            };
+        }
+        let first = (0..81).find(|i| !p[*i].is_solved()).unwrap();
+        p[first] = CandidateSet::new(0b111);
+        let p = Sudoku::new(p);
+        assert_eq!(None, p.find_unique_rectangle().next());
+        let step = p.find_bug_plus_one().next().unwrap();
+        assert_eq!(Technique::BugPlusOne, step.technique);
+        assert_eq!(vec![Candidate::new(first, 3)], step.placements);
+    }
+
+    #[test]
+    fn test_uniqueness_is_opt_in() {
+        let s = "..279...8.9...6.7.3..........86...41..1.........5..3976.3.78..4.......39.8.1.....";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert_eq!(0, sr.unique_rectangle);
+        assert_eq!(0, sr.bug_plus_one);
+
+        let options = SolveOptions {
+            uniqueness: true,
+            ..Default::default()
+        };
+        let sr = parse_sudoku(s).unwrap().try_solve_with(options);
+        assert!(sr.is_solved);
+        assert!(sr.is_valid);
+        assert!(sr.bug_plus_one > 0);
+    }
+
+    #[test]
+    fn test_hidden_unique_rectangle() {
+        let s = ".1.4.3.5...9.....14...........89.2..........3.48..6...8.2..59..7......3..3..8.56.";
+        let options = SolveOptions {
+            uniqueness: true,
+            ..Default::default()
+        };
+        let mut p = parse_sudoku(s).unwrap();
+        let step = loop {
+            let step = next_step_with(&p, options).unwrap();
+            if step.technique == Technique::HiddenUniqueRectangle {
+                break step;
+            }
+            // Until then the strict solver finds the same steps.
+            assert_eq!(Some(&step), next_step(&p).as_ref());
+            step.apply(&mut p);
+        };
+        assert_eq!(vec![Candidate::new(to_index(5, 4), 1)], step.eliminations);
+    }
+}
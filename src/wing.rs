@@ -0,0 +1,127 @@
+use crate::step::{to_digit, Step, Technique};
+use crate::sudoku::Sudoku;
+
+pub trait Wing {
+    fn find_xy_wing(&self) -> impl Iterator<Item = Step> + '_;
+    fn find_xyz_wing(&self) -> impl Iterator<Item = Step> + '_;
+}
+
+/// Unsolved positions with exactly `n` candidates.
+fn with_candidates(sudoku: &Sudoku, n: usize) -> impl Iterator<Item = usize> + '_ {
+    (0..81).filter(move |i| !sudoku[*i].is_solved() && sudoku[*i].num_candidates() == n)
+}
+
+/// Find the two pincers for `pivot` and build the step if
+/// it can remove anything.
+///
+/// The pincers each share one candidate with the pivot
+/// and both have the same other candidate, `z`.
+fn wing_step(sudoku: &Sudoku, technique: Technique, pivot: usize) -> Vec<Step> {
+    let pivot_mask = sudoku[pivot].get_candidates();
+    // The pincers are always bi-value positions that the pivot can see.
+    let pincers: Vec<usize> = sudoku
+        .peers(pivot)
+        .filter(|i| !sudoku[*i].is_solved() && sudoku[*i].num_candidates() == 2)
+        .collect();
+    let mut steps = Vec::new();
+    for (n, a) in pincers.iter().enumerate() {
+        for b in pincers[n + 1..].iter() {
+            let a_mask = sudoku[*a].get_candidates();
+            let b_mask = sudoku[*b].get_candidates();
+            let z = a_mask & b_mask;
+            if a_mask == b_mask || z.count_ones() != 1 {
+                continue;
+            }
+            let (valid, cells) = match technique {
+                // Pivot is xy, the pincers xz and yz
+                Technique::XYWing => (
+                    z & pivot_mask == 0 && (a_mask | b_mask) & !z == pivot_mask,
+                    vec![*a, *b],
+                ),
+                // Pivot is xyz, the pincers xz and yz
+                _ => ((a_mask | b_mask) == pivot_mask, vec![pivot, *a, *b]),
+            };
+            if !valid {
+                continue;
+            }
+            let eliminations = sudoku.common_peer_eliminations(&cells, z);
+            if eliminations.is_empty() {
+                continue;
+            }
+            steps.push(Step {
+                technique,
+                units: vec![],
+                cells: vec![pivot, *a, *b],
+                digits: vec![to_digit(z)],
+                eliminations,
+                placements: vec![],
+                links: vec![],
+                sets: vec![],
+            });
+        }
+    }
+    steps
+}
+
+impl Wing for Sudoku {
+    fn find_xy_wing(&self) -> impl Iterator<Item = Step> + '_ {
+        with_candidates(self, 2).flat_map(move |pivot| wing_step(self, Technique::XYWing, pivot))
+    }
+
+    fn find_xyz_wing(&self) -> impl Iterator<Item = Step> + '_ {
+        with_candidates(self, 3).flat_map(move |pivot| wing_step(self, Technique::XYZWing, pivot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_helpers::to_index;
+    use crate::parse::*;
+    use crate::solve::Solveable;
+    use crate::step::Candidate;
+    use crate::test_helpers::with_masks;
+
+    #[test]
+    fn test_xy_wing() {
+        let s = ".....7....9...1.......45..6....2.....36...41.5.....8.9........4....18....815...32";
+        let sr = parse_sudoku(s).unwrap().try_solve();
+        assert!(sr.is_solved);
+        assert!(sr.xy_wing > 0);
+    }
+
+    #[test]
+    fn test_xy_wing_masks() {
+        // Pivot r1c1 is 1 or 2, r1c5 is 1 or 3, and r5c1 is 2 or 3.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b011),
+            (to_index(0, 4), 0b101),
+            (to_index(4, 0), 0b110),
+        ]);
+        let step = p.find_xy_wing().next().unwrap();
+        assert_eq!(Technique::XYWing, step.technique);
+        assert_eq!(vec![3], step.digits);
+        assert_eq!(vec![Candidate::new(to_index(4, 4), 3)], step.eliminations);
+    }
+
+    #[test]
+    fn test_xyz_wing_masks() {
+        // Pivot r1c1 is 1, 2, or 3, r1c5 is 1 or 3, and r2c2 is 2 or 3.
+        let p = with_masks(&[
+            (to_index(0, 0), 0b111),
+            (to_index(0, 4), 0b101),
+            (to_index(1, 1), 0b110),
+        ]);
+        assert!(p.find_xy_wing().next().is_none());
+        let step = p.find_xyz_wing().next().unwrap();
+        assert_eq!(Technique::XYZWing, step.technique);
+        assert_eq!(vec![3], step.digits);
+        assert_eq!(
+            vec![
+                Candidate::new(to_index(0, 1), 3),
+                Candidate::new(to_index(0, 2), 3)
+            ],
+            step.eliminations
+        );
+    }
+}